@@ -45,43 +45,791 @@ let path = Path::new("fixtures/cccanto-test.txt");
 let cedict = Cedict::from_path(path).unwrap();
 assert_eq!(cedict.entries.len(), 3);
 ```
+
+Comment lines before the first entry are kept as the dictionary's `CedictMetadata`:
+
+```
+# use cccedict::cedict::Cedict;
+# use std::path::Path;
+let cedict = Cedict::from_path(Path::new("fixtures/cccanto-test.txt")).unwrap();
+assert_eq!(cedict.metadata.preamble[2], "Version 2017-02-02");
+```
+
+`Cedict::from_str` skips lines that are not valid entries. Use `Cedict::parse_with_diagnostics` to
+find out which lines were skipped, or `Cedict::parse_strict` to reject the input instead:
+
+```
+# use cccedict::cedict::Cedict;
+let cedict_entries = "\
+你嘅 你嘅 [ni3 ge2] {nei5 ge3} /your's (spoken)/
+你地 你地 [ni3 di4 {nei5 dei6} /you guys; you all/";
+
+let (cedict, diagnostics) = Cedict::parse_with_diagnostics(cedict_entries);
+assert_eq!(cedict.entries.len(), 1);
+assert_eq!(diagnostics[0].line_number, 2);
+
+assert!(Cedict::parse_strict(cedict_entries).is_err());
+```
+
+A `Cedict` can be written back out as CC-CEDICT text, header included, with `Cedict::to_file`,
+`Cedict::to_path` or its `Display` implementation:
+
+```
+# use cccedict::cedict::Cedict;
+# use std::str::FromStr;
+let cedict = Cedict::from_path("fixtures/cccanto-test.txt").unwrap();
+
+let mut output: Vec<u8> = vec![];
+cedict.to_file(&mut output).unwrap();
+
+let reparsed = Cedict::from_str(&String::from_utf8(output).unwrap()).unwrap();
+assert_eq!(reparsed, cedict);
+```
+
+Entries can be looked up by headword in either script. Every entry for a headword is returned, in
+the order they appear in the file:
+
+```
+# use cccedict::cedict::Cedict;
+# use std::str::FromStr;
+let cedict = Cedict::from_str("\
+行 行 [hang2] /row; line/
+銀行 银行 [yin2 hang2] /bank/
+行 行 [xing2] /to walk; to go/").unwrap();
+
+let entries = cedict.lookup_traditional("行");
+assert_eq!(entries.len(), 2);
+assert_eq!(entries[1].definitions, Some(vec!["to walk; to go".to_string()]));
+
+assert_eq!(cedict.lookup_simplified("银行")[0].traditional, "銀行");
+assert_eq!(cedict.lookup("銀行"), cedict.lookup("银行"));
+```
+
+Entries can also be searched by their English definitions with `Cedict::search_english`, which
+ranks them as described in the `english_index` module, and by reading with
+`Cedict::search_pinyin` and `Cedict::search_jyutping`, where tones are optional as described in
+the `reading_index` module.
+
+Readings can also be searched fuzzily, for sounds that learners mix up, with
+`Cedict::fuzzy_search_pinyin` and `Cedict::fuzzy_search_jyutping`, as described in the `fuzzy`
+module.
+
+Entries can be suggested for a partial query with `Cedict::suggest`, as described in the `prefix`
+module.
+
+The headword index is built when the dictionary is loaded. The English, reading and prefix
+indexes are larger, so each is only built the first time it is needed. If you change
+`Cedict::entries` afterwards, call `Cedict::rebuild_index` to bring the indexes up to date.
 */
 
 pub use crate::cedict_entry::CedictEntry;
-use crate::errors::BoxError;
+pub use crate::cedict_metadata::CedictMetadata;
+pub use crate::cedict_reader::CedictReader;
+use crate::english_index::{self, EnglishIndex};
+use crate::errors::{BoxError, CedictError, LineDiagnostic};
+use crate::fuzzy::{FuzzyOptions, FuzzyQuery};
+use crate::index::HeadwordIndex;
+use crate::pinyin;
+use crate::prefix::PrefixIndex;
+use crate::reading_index::{self, ReadingIndex};
+use crate::syllable::{Jyutping, Pinyin, Romanization, Syllable};
 use std::str::FromStr;
+use std::sync::OnceLock;
 
+use std::fmt;
 use std::fs::File;
-use std::io::Read;
+use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default)]
 pub struct Cedict {
+    pub metadata: CedictMetadata,
     pub entries: Vec<CedictEntry>,
+    index: Indexes,
+}
+
+/// Everything `Cedict` looks entries up by. The headword index is kept in step with
+/// `Cedict::entries`; the others are built from them on first use.
+#[derive(Debug, Clone, Default)]
+struct Indexes {
+    headwords: HeadwordIndex,
+    english: OnceLock<EnglishIndex>,
+    pinyin: OnceLock<ReadingIndex>,
+    jyutping: OnceLock<ReadingIndex>,
+    prefixes: OnceLock<PrefixIndex>,
+}
+
+impl Indexes {
+    fn new(entries: &[CedictEntry]) -> Self {
+        Indexes {
+            headwords: HeadwordIndex::new(entries),
+            ..Indexes::default()
+        }
+    }
 }
 
+/// Dictionaries are equal when their metadata and entries are, whatever the state of their indexes.
+impl PartialEq for Cedict {
+    fn eq(&self, other: &Self) -> bool {
+        self.metadata == other.metadata && self.entries == other.entries
+    }
+}
+
+impl Eq for Cedict {}
+
 impl FromStr for Cedict {
     type Err = BoxError;
 
     fn from_str(cedict_entries: &str) -> Result<Self, Self::Err> {
-        let entries: Vec<CedictEntry> = cedict_entries
-            .lines()
-            .filter_map(|line| CedictEntry::new(line).ok())
-            .collect();
-        Ok(Cedict { entries })
+        let (cedict, _) = Self::parse_with_diagnostics(cedict_entries);
+        Ok(cedict)
     }
 }
 
 impl Cedict {
-    pub fn from_file<R: Read>(mut cedict_reader: R) -> Result<Self, BoxError> {
-        let mut cedict_entries: String = "".into();
-        cedict_reader.read_to_string(&mut cedict_entries)?;
+    pub fn new(metadata: CedictMetadata, entries: Vec<CedictEntry>) -> Self {
+        let index = Indexes::new(&entries);
+        Cedict {
+            metadata,
+            entries,
+            index,
+        }
+    }
+
+    /// Parses every line it can, returning a `LineDiagnostic` for each non-comment, non-blank
+    /// line that was skipped because it is not a valid entry.
+    pub fn parse_with_diagnostics(cedict_entries: &str) -> (Self, Vec<LineDiagnostic>) {
+        let mut cedict = Cedict::default();
+        let mut diagnostics = vec![];
+
+        for (idx, line) in cedict_entries.lines().enumerate() {
+            if let Err(diagnostic) = cedict.push_line(idx + 1, line) {
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        (cedict, diagnostics)
+    }
+
+    /// Parses the whole input, failing on the first non-comment, non-blank line that is not a
+    /// valid entry.
+    pub fn parse_strict(cedict_entries: &str) -> Result<Self, CedictError> {
+        let mut cedict = Cedict::default();
+
+        for (idx, line) in cedict_entries.lines().enumerate() {
+            cedict
+                .push_line(idx + 1, line)
+                .map_err(CedictError::InvalidLine)?;
+        }
+
+        Ok(cedict)
+    }
+
+    fn push_line(&mut self, line_number: usize, line: &str) -> Result<(), LineDiagnostic> {
+        if self.entries.is_empty() && self.metadata.push_header_line(line) {
+            return Ok(());
+        }
 
-        Self::from_str(&cedict_entries)
+        match CedictEntry::parse_line(line) {
+            Ok(Some(entry)) => {
+                self.index.headwords.push(self.entries.len(), &entry);
+                self.entries.push(entry);
+                Ok(())
+            }
+            Ok(None) => Ok(()),
+            Err(error) => Err(LineDiagnostic {
+                line_number,
+                line: line.to_string(),
+                error,
+            }),
+        }
+    }
+
+    /// Reads entries line by line, skipping lines that are not valid entries. I/O errors,
+    /// including invalid UTF-8, are returned.
+    pub fn from_file<R: Read>(cedict_reader: R) -> Result<Self, BoxError> {
+        let mut reader = CedictReader::new(BufReader::new(cedict_reader));
+        let mut entries = vec![];
+
+        for result in &mut reader {
+            match result {
+                Ok((_, entry)) => entries.push(entry),
+                Err(CedictError::InvalidLine(_)) => continue,
+                Err(err) => return Err(Box::new(err)),
+            }
+        }
+
+        Ok(Cedict::new(reader.into_metadata(), entries))
     }
 
     pub fn from_path<P: AsRef<Path>>(cedict_path: P) -> Result<Self, BoxError> {
         let cedict_file = File::open(cedict_path)?;
         Self::from_file(cedict_file)
     }
+
+    /// Writes the header and entries as CC-CEDICT text, one entry per line in their current order.
+    pub fn to_file<W: Write>(&self, mut cedict_writer: W) -> Result<(), BoxError> {
+        write!(cedict_writer, "{}", self)?;
+        cedict_writer.flush()?;
+
+        Ok(())
+    }
+
+    pub fn to_path<P: AsRef<Path>>(&self, cedict_path: P) -> Result<(), BoxError> {
+        let cedict_file = File::create(cedict_path)?;
+        self.to_file(BufWriter::new(cedict_file))
+    }
+
+    /// Checks the `#! entries=` header against the number of parsed entries, so that a truncated
+    /// download is caught at load time. Dictionaries without that header always pass.
+    pub fn verify_entry_count(&self) -> Result<(), CedictError> {
+        let declared = match self.metadata.entries() {
+            Some(declared) => declared
+                .parse::<usize>()
+                .map_err(|_| CedictError::InvalidEntryCount(declared.to_string()))?,
+            None => return Ok(()),
+        };
+
+        if declared == self.entries.len() {
+            Ok(())
+        } else {
+            Err(CedictError::EntryCountMismatch {
+                declared,
+                parsed: self.entries.len(),
+            })
+        }
+    }
+}
+
+impl Cedict {
+    /// All entries whose traditional headword is `headword`, in file order.
+    pub fn lookup_traditional(&self, headword: &str) -> Vec<&CedictEntry> {
+        self.entries_at(self.index.headwords.traditional(headword), |entry| {
+            entry.traditional == headword
+        })
+    }
+
+    /// All entries whose simplified headword is `headword`, in file order.
+    pub fn lookup_simplified(&self, headword: &str) -> Vec<&CedictEntry> {
+        self.entries_at(self.index.headwords.simplified(headword), |entry| {
+            entry.simplified == headword
+        })
+    }
+
+    /// All entries whose traditional or simplified headword is `headword`, in file order.
+    pub fn lookup(&self, headword: &str) -> Vec<&CedictEntry> {
+        self.entries_at(&self.index.headwords.any(headword), |entry| {
+            entry.traditional == headword || entry.simplified == headword
+        })
+    }
+
+    /// Entries whose English definitions match `query`, best match first, each with its score
+    /// from `english_index::score`. Entries with equal scores are kept in file order.
+    pub fn search_english(&self, query: &str) -> Vec<(f64, &CedictEntry)> {
+        let english = self
+            .index
+            .english
+            .get_or_init(|| EnglishIndex::new(&self.entries));
+        let mut results: Vec<(f64, &CedictEntry)> = english
+            .candidates(query)
+            .iter()
+            .filter_map(|position| self.entries.get(*position))
+            .map(|entry| (english_index::score(query, entry), entry))
+            .filter(|(score, _)| *score > 0.0)
+            .collect();
+        results.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        results
+    }
+
+    /// Entries whose pinyin is `query`, in file order. Tones can be given as numbers or marks, or
+    /// left out, and syllables can be run together, so `ni3 hao3`, `nihao`, `ni hao3` and `nǐhǎo`
+    /// all find 你好.
+    pub fn search_pinyin(&self, query: &str) -> Vec<&CedictEntry> {
+        // Tone marks are rewritten as numbers, which is all `reading_index::matches` understands.
+        // Syllables stay apart so that separators like the apostrophe in `xi'an` still count.
+        let query = match pinyin::parse_tone_marked(query) {
+            Ok(syllables) => syllables
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" "),
+            Err(_) => query.to_string(),
+        };
+
+        let index = self
+            .index
+            .pinyin
+            .get_or_init(|| reading_index(&self.entries, |entry| entry.pinyin.as_deref()));
+        self.entries_at(index.candidates(&query), |entry| {
+            entry
+                .pinyin
+                .as_ref()
+                .is_some_and(|reading| reading_index::matches(&query, reading))
+        })
+    }
+
+    /// Entries whose jyutping is `query`, in file order, including entries with no pinyin at all.
+    /// Tones are optional and syllables can be run together, so `nei5 hou2`, `neihou` and
+    /// `nei hou2` all find 你好.
+    pub fn search_jyutping(&self, query: &str) -> Vec<&CedictEntry> {
+        let index = self
+            .index
+            .jyutping
+            .get_or_init(|| reading_index(&self.entries, |entry| entry.jyutping.as_deref()));
+        self.entries_at(index.candidates(query), |entry| {
+            entry
+                .jyutping
+                .as_ref()
+                .is_some_and(|reading| reading_index::matches(query, reading))
+        })
+    }
+
+    /// Entries whose pinyin is close to `query` under `options`, each with its distance in edits,
+    /// closest first and otherwise in file order.
+    pub fn fuzzy_search_pinyin(
+        &self,
+        query: &str,
+        options: &FuzzyOptions,
+    ) -> Vec<(usize, &CedictEntry)> {
+        let query = FuzzyQuery::<Pinyin>::new(query, options);
+        self.fuzzy_search(|entry| query.distance(entry.pinyin.as_ref()?))
+    }
+
+    /// Entries whose jyutping is close to `query` under `options`, each with its distance in
+    /// edits, closest first and otherwise in file order.
+    pub fn fuzzy_search_jyutping(
+        &self,
+        query: &str,
+        options: &FuzzyOptions,
+    ) -> Vec<(usize, &CedictEntry)> {
+        let query = FuzzyQuery::<Jyutping>::new(query, options);
+        self.fuzzy_search(|entry| query.distance(entry.jyutping.as_ref()?))
+    }
+
+    /// Scores every entry, as fuzzy matches can't be narrowed down by an index.
+    fn fuzzy_search<F>(&self, distance: F) -> Vec<(usize, &CedictEntry)>
+    where
+        F: Fn(&CedictEntry) -> Option<usize>,
+    {
+        let mut results: Vec<(usize, &CedictEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| distance(entry).map(|distance| (distance, entry)))
+            .collect();
+        results.sort_by_key(|(distance, _)| *distance);
+        results
+    }
+
+    /// Up to `limit` entries that can be typed starting with `prefix`, by headword in either script
+    /// or by pinyin or jyutping without tones. Entries with shorter headwords come first, and are
+    /// otherwise in file order.
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Vec<&CedictEntry> {
+        self.prefixes()
+            .suggest(prefix, limit, &self.entries)
+            .iter()
+            .filter_map(|position| self.entries.get(*position))
+            .collect()
+    }
+
+    /// Like `Cedict::suggest`, but ranking entries by `rank`, lowest first. Entries that rank the
+    /// same are kept in file order. Every entry matching `prefix` is ranked, so short prefixes
+    /// are slower than with `Cedict::suggest`.
+    pub fn suggest_by_key<K, F>(&self, prefix: &str, limit: usize, mut rank: F) -> Vec<&CedictEntry>
+    where
+        K: Ord,
+        F: FnMut(&&CedictEntry) -> K,
+    {
+        let mut ranked: Vec<(K, usize, &CedictEntry)> = self
+            .prefixes()
+            .find(prefix)
+            .into_iter()
+            .filter_map(|position| Some((position, self.entries.get(position)?)))
+            .map(|(position, entry)| (rank(&entry), position, entry))
+            .collect();
+
+        // Only the best `limit` need to be sorted
+        if limit == 0 {
+            return vec![];
+        }
+        if limit < ranked.len() {
+            ranked.select_nth_unstable_by(limit - 1, |a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+            ranked.truncate(limit);
+        }
+        ranked.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+        ranked.into_iter().map(|(_, _, entry)| entry).collect()
+    }
+
+    /// Rebuilds the headword index and clears the others so that they are rebuilt from
+    /// `Cedict::entries` when next needed, which is needed after changing them.
+    pub fn rebuild_index(&mut self) {
+        self.index = Indexes::new(&self.entries);
+    }
+
+    fn prefixes(&self) -> &PrefixIndex {
+        self.index
+            .prefixes
+            .get_or_init(|| PrefixIndex::new(&self.entries))
+    }
+
+    /// The entries at `positions`. Entries that no longer match, because `entries` changed since
+    /// the index was built, are left out rather than returned by mistake.
+    fn entries_at<F>(&self, positions: &[usize], matches: F) -> Vec<&CedictEntry>
+    where
+        F: Fn(&CedictEntry) -> bool,
+    {
+        positions
+            .iter()
+            .filter_map(|position| self.entries.get(*position))
+            .filter(|entry| matches(entry))
+            .collect()
+    }
+}
+
+fn reading_index<R, F>(entries: &[CedictEntry], reading: F) -> ReadingIndex
+where
+    R: Romanization,
+    F: Fn(&CedictEntry) -> Option<&[Syllable<R>]>,
+{
+    let mut index = ReadingIndex::default();
+    for (position, entry) in entries.iter().enumerate() {
+        if let Some(reading) = reading(entry) {
+            index.push(position, reading);
+        }
+    }
+    index
+}
+
+impl fmt::Display for Cedict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.metadata)?;
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOMOGRAPHS: &str = "\
+行 行 [hang2] /row; line/
+銀行 银行 [yin2 hang2] /bank/
+行 行 [xing2] /to walk; to go/
+發 发 [fa1] /to send out/
+髮 发 [fa4] /hair/";
+
+    #[test]
+    fn test_lookup_homographs_in_file_order() {
+        let cedict = Cedict::from_str(HOMOGRAPHS).unwrap();
+
+        let pinyin: Vec<String> = cedict
+            .lookup_traditional("行")
+            .iter()
+            .map(|entry| entry.pinyin.as_ref().unwrap()[0].to_string())
+            .collect();
+        assert_eq!(pinyin, vec!["hang2", "xing2"]);
+
+        let traditional: Vec<&str> = cedict
+            .lookup_simplified("发")
+            .iter()
+            .map(|entry| entry.traditional.as_str())
+            .collect();
+        assert_eq!(traditional, vec!["發", "髮"]);
+    }
+
+    #[test]
+    fn test_lookup_either_script() {
+        let cedict = Cedict::from_str(HOMOGRAPHS).unwrap();
+
+        assert_eq!(cedict.lookup("发").len(), 2);
+        assert_eq!(cedict.lookup("髮").len(), 1);
+        // Entries whose headwords are the same in both scripts are only returned once
+        assert_eq!(cedict.lookup("行").len(), 2);
+        assert!(cedict.lookup("走").is_empty());
+        assert!(cedict.lookup_traditional("发").is_empty());
+    }
+
+    #[test]
+    fn test_lookup_after_loading_from_file() {
+        let cedict = Cedict::from_path("fixtures/cccanto-test.txt").unwrap();
+        let entry = &cedict.entries[0];
+
+        assert_eq!(cedict.lookup_traditional(&entry.traditional), vec![entry]);
+    }
+
+    #[test]
+    fn test_search_english() {
+        let cedict = Cedict::from_str(
+            "\
+放映 放映 [fang4 ying4] /to show (a movie); to screen/
+电影 电影 [dian4 ying3] /movie; film; CL:部[bu4],場|场[chang3]/
+电影明星 电影明星 [dian4 ying3 ming2 xing1] /movie star/",
+        )
+        .unwrap();
+
+        let headwords: Vec<&str> = cedict
+            .search_english("movie")
+            .iter()
+            .map(|(_, entry)| entry.simplified.as_str())
+            .collect();
+        assert_eq!(headwords, vec!["电影", "电影明星", "放映"]);
+
+        assert_eq!(
+            cedict.search_english("Movie Star")[0].1.simplified,
+            "电影明星"
+        );
+        assert!(cedict.search_english("television").is_empty());
+        assert!(cedict.search_english("").is_empty());
+    }
+
+    #[test]
+    fn test_search_pinyin() {
+        let cedict = Cedict::from_str(
+            "\
+先 先 [xian1] /early; prior/
+西安 西安 [Xi1 an1] /Xi'an/
+你好 你好 [ni3 hao3] /hello/
+吗 吗 [ma5] /question particle/
+绿 绿 [lu:4] /green/
+反感 反感 [fan3 gan3] /to be disgusted with/",
+        )
+        .unwrap();
+
+        let simplified = |query| -> Vec<&str> {
+            cedict
+                .search_pinyin(query)
+                .iter()
+                .map(|entry| entry.simplified.as_str())
+                .collect()
+        };
+
+        assert_eq!(simplified("ni3 hao3"), vec!["你好"]);
+        assert_eq!(simplified("nihao"), vec!["你好"]);
+        assert_eq!(simplified("ni hao3"), vec!["你好"]);
+        assert!(simplified("ni3 hao1").is_empty());
+        assert_eq!(simplified("xian"), vec!["先", "西安"]);
+        assert_eq!(simplified("xi1 an1"), vec!["西安"]);
+        assert_eq!(simplified("Xī'ān"), vec!["西安"]);
+        assert_eq!(simplified("xi'an"), vec!["西安"]);
+        assert_eq!(simplified("xi an"), vec!["西安"]);
+        assert_eq!(simplified("fangan"), vec!["反感"]);
+        assert_eq!(simplified("ma"), vec!["吗"]);
+        assert_eq!(simplified("ma5"), vec!["吗"]);
+        assert_eq!(simplified("lv4"), vec!["绿"]);
+        assert_eq!(simplified("lü"), vec!["绿"]);
+        assert!(simplified("").is_empty());
+    }
+
+    #[test]
+    fn test_search_jyutping() {
+        let cedict = Cedict::from_str(
+            "\
+𠌥 𠆿 [] {wu1} /(verb) to lean over; to stoop/
+你好 你好 [ni3 hao3] {nei5 hou2} /hello/
+一世人 一世人 [yi1 shi4 ren2] {jat1 sai3 jan4} /one's entire life/",
+        )
+        .unwrap();
+
+        let simplified = |query| -> Vec<&str> {
+            cedict
+                .search_jyutping(query)
+                .iter()
+                .map(|entry| entry.simplified.as_str())
+                .collect()
+        };
+
+        assert_eq!(simplified("wu1"), vec!["𠆿"]);
+        assert_eq!(simplified("wu"), vec!["𠆿"]);
+        assert!(simplified("wu2").is_empty());
+        assert_eq!(simplified("nei5 hou2"), vec!["你好"]);
+        assert_eq!(simplified("neihou"), vec!["你好"]);
+        assert_eq!(simplified("jat1saijan4"), vec!["一世人"]);
+        assert!(simplified("ni3 hao3").is_empty());
+        assert!(cedict.search_pinyin("wu1").is_empty());
+    }
+
+    #[test]
+    fn test_suggest() {
+        let cedict = Cedict::from_str(
+            "\
+你們 你们 [ni3 men5] {nei5 mun4} /you (plural)/
+你好 你好 [ni3 hao3] {nei5 hou2} /hello/
+𠌥 𠆿 [] {wu1} /(verb) to lean over; to stoop/
+你 你 [ni3] {nei5} /you/",
+        )
+        .unwrap();
+
+        let simplified = |prefix, limit| -> Vec<&str> {
+            cedict
+                .suggest(prefix, limit)
+                .iter()
+                .map(|entry| entry.simplified.as_str())
+                .collect()
+        };
+
+        assert_eq!(simplified("你", 10), vec!["你", "你们", "你好"]);
+        assert_eq!(simplified("你們", 10), vec!["你们"]);
+        assert_eq!(simplified("nih", 10), vec!["你好"]);
+        assert_eq!(simplified("ni3 m", 10), vec!["你们"]);
+        assert_eq!(simplified("nei", 2), vec!["你", "你们"]);
+        assert_eq!(simplified("wu", 10), vec!["𠆿"]);
+        assert!(simplified("ni", 0).is_empty());
+        assert!(simplified("", 10).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        let cedict = Cedict::from_str(
+            "\
+宗国 宗国 [zong1 guo2] /ancestral state/
+中国 中国 [Zhong1 guo2] {zung1 gwok3} /China/
+你好 你好 [ni3 hao3] {nei5 hou2} /hello/
+我 我 [wo3] {ngo5} /I; me/",
+        )
+        .unwrap();
+        let pinyin = FuzzyOptions::pinyin();
+        let jyutping = FuzzyOptions::jyutping();
+
+        let results = cedict.fuzzy_search_pinyin("zong1 guo2", &pinyin);
+        let simplified: Vec<&str> = results
+            .iter()
+            .map(|(_, entry)| entry.simplified.as_str())
+            .collect();
+        assert_eq!(simplified, vec!["宗国", "中国"]);
+        assert!(results.iter().all(|(distance, _)| *distance == 0));
+
+        assert_eq!(cedict.fuzzy_search_pinyin("li3 hao4", &pinyin)[0].0, 1);
+        assert!(cedict.fuzzy_search_pinyin("li3 hou4", &pinyin).is_empty());
+
+        assert_eq!(
+            cedict.fuzzy_search_jyutping("lei5 hou2", &jyutping)[0]
+                .1
+                .simplified,
+            "你好"
+        );
+        assert_eq!(
+            cedict.fuzzy_search_jyutping("o5", &jyutping)[0]
+                .1
+                .simplified,
+            "我"
+        );
+        assert!(cedict.fuzzy_search_jyutping("", &jyutping).is_empty());
+    }
+
+    #[test]
+    fn test_rebuild_index() {
+        let mut cedict = Cedict::from_str(HOMOGRAPHS).unwrap();
+        assert_eq!(cedict.lookup_traditional("行").len(), 2);
+        cedict.entries.remove(0);
+
+        // A stale index can miss entries, but never returns the wrong one
+        assert!(cedict.lookup_traditional("行").is_empty());
+        assert!(cedict.lookup_traditional("發").is_empty());
+
+        cedict.rebuild_index();
+        assert_eq!(cedict.lookup_traditional("發").len(), 1);
+        assert_eq!(cedict.lookup_traditional("行").len(), 1);
+    }
+
+    #[test]
+    fn test_indexes_are_built_on_first_use() {
+        let mut cedict = Cedict::from_str(HOMOGRAPHS).unwrap();
+        cedict.entries.remove(0);
+
+        // The headword index was built at load time, but the prefix index is built from the
+        // entries as they are now
+        assert!(cedict.lookup_traditional("行").is_empty());
+        assert_eq!(cedict.suggest("fa", 10).len(), 2);
+    }
+
+    #[test]
+    fn test_header_is_parsed_into_metadata() {
+        let cedict = Cedict::from_str(
+            "\
+# CC-CEDICT
+#! version=1
+#! entries=2
+你好 你好 [ni3 hao3] /hello/
+# not part of the header
+再見 再见 [zai4 jian4] /goodbye/",
+        )
+        .unwrap();
+
+        assert_eq!(cedict.metadata.preamble, vec!["CC-CEDICT"]);
+        assert_eq!(cedict.metadata.version(), Some("1"));
+        assert_eq!(cedict.entries.len(), 2);
+        assert_eq!(cedict.verify_entry_count(), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_entry_count() {
+        let truncated = Cedict::from_str("#! entries=3\n你好 你好 [ni3 hao3] /hello/").unwrap();
+        assert_eq!(
+            truncated.verify_entry_count(),
+            Err(CedictError::EntryCountMismatch {
+                declared: 3,
+                parsed: 1
+            })
+        );
+
+        let invalid = Cedict::from_str("#! entries=many\n你好 你好 [ni3 hao3] /hello/").unwrap();
+        assert_eq!(
+            invalid.verify_entry_count(),
+            Err(CedictError::InvalidEntryCount("many".into()))
+        );
+
+        let undeclared = Cedict::from_str("你好 你好 [ni3 hao3] /hello/").unwrap();
+        assert_eq!(undeclared.verify_entry_count(), Ok(()));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let cedict_text = "\
+# CC-CEDICT
+#
+#! version=1
+#! entries=4
+抄字典 抄字典 [chao1 zi4 dian3] {caau3 zi6 din2} /to search/flip through a dictionary/
+以身作則 以身作则 [yi3 shen1 zuo4 ze2] /to set an example (idiom)/
+𠌥 𠆿 [] {wu1} /(verb) to lean over; to stoop/
+你好 你好 [ni3 hao3] //
+";
+
+        let cedict = Cedict::from_str(cedict_text).unwrap();
+        assert_eq!(cedict.to_string(), cedict_text);
+        assert_eq!(Cedict::from_str(&cedict.to_string()).unwrap(), cedict);
+    }
+
+    #[test]
+    fn test_parse_with_diagnostics() {
+        let (cedict, diagnostics) = Cedict::parse_with_diagnostics(
+            "\
+# header
+你好 你好 [ni3 hao3] /hello/
+
+# a comment between entries
+not an entry
+再見 再见 [zai4 jian4] /goodbye/",
+        );
+
+        assert_eq!(cedict.entries.len(), 2);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line_number, 5);
+        assert_eq!(diagnostics[0].line, "not an entry");
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let valid = "# header\n你好 你好 [ni3 hao3] /hello/\n\n再見 再见 [zai4 jian4] /goodbye/";
+        assert_eq!(Cedict::parse_strict(valid).unwrap().entries.len(), 2);
+
+        let invalid = "你好 你好 [ni3 hao3] /hello/\n再見 再见 [zai4 jian4 /goodbye/\nbad";
+        match Cedict::parse_strict(invalid) {
+            Err(CedictError::InvalidLine(diagnostic)) => assert_eq!(diagnostic.line_number, 2),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
 }
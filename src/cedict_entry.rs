@@ -26,67 +26,361 @@ assert_eq!(entry.jyutping, Some(
 ));
 assert_eq!(entry.definitions, Some(vec!["how are you?".to_string()]));
 ```
+
+Entries format back into canonical CC-CEDICT lines:
+
+```
+# use cccedict::cedict_entry::*;
+let entry = CedictEntry::new("你好嗎 你好吗 [ni3hao3 ma5]   {nei5 hou2 maa1} / how are you? /").unwrap();
+assert_eq!(entry.to_string(), "你好嗎 你好吗 [ni3 hao3 ma5] {nei5 hou2 maa1} /how are you?/");
+```
+
+A trailing comment after the definitions is kept in `CedictEntry::comment`:
+
+```
+# use cccedict::cedict_entry::*;
+let entry = CedictEntry::new("一世人 一世人 [yi1 shi4 ren2] {jat1 sai3 jan4} /lifetime/ # adapted from cc-cedict").unwrap();
+assert_eq!(entry.comment, Some("adapted from cc-cedict".to_string()));
+```
 */
 
-use crate::errors::{BoxError, CedictEntryError};
-pub use crate::syllable::Syllable;
+use crate::errors::{CedictEntryError, CedictEntryErrorKind, SyllableError};
+use crate::ipa::{Ipa, ToneNotation};
+use crate::sandhi;
+pub use crate::syllable::{Jyutping, Pinyin, Romanization, Syllable, SyllableRef};
+use std::fmt;
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct CedictEntry {
     pub traditional: String,
     pub simplified: String,
-    pub pinyin: Option<Vec<Syllable>>,
-    pub jyutping: Option<Vec<Syllable>>,
+    pub pinyin: Option<Vec<Syllable<Pinyin>>>,
+    pub jyutping: Option<Vec<Syllable<Jyutping>>>,
     pub definitions: Option<Vec<String>>,
+    /// A trailing `# comment` after the definitions, without the `#` and surrounding whitespace.
+    /// CC-Canto uses these to record provenance, e.g. `# adapted from cc-cedict`.
+    pub comment: Option<String>,
 }
 
 impl CedictEntry {
-    pub fn new(input: &str) -> Result<CedictEntry, BoxError> {
-        match parsers::parse_line(input).unwrap_or(("", None)) {
-            (_, Some(entry)) => Ok(entry),
-            (_, None) => Err(Box::new(CedictEntryError)),
+    pub fn new(input: &str) -> Result<CedictEntry, CedictEntryError> {
+        CedictEntryRef::new(input).map(|entry| entry.to_owned())
+    }
+
+    /// Like `CedictEntry::new`, but returns `Ok(None)` for blank and comment-only lines instead
+    /// of an error.
+    pub fn parse_line(input: &str) -> Result<Option<CedictEntry>, CedictEntryError> {
+        CedictEntryRef::parse_line(input).map(|entry| entry.map(|entry| entry.to_owned()))
+    }
+
+    /// Checks every pinyin and jyutping syllable against the syllable inventories, returning
+    /// all of the syllables that aren't valid.
+    ///
+    /// ```
+    /// # use cccedict::cedict_entry::*;
+    /// let entry = CedictEntry::new("你好 你好 [ni3 hao3] {nei5 hou2} /hello/").unwrap();
+    /// assert!(entry.validate().is_ok());
+    ///
+    /// let entry = CedictEntry::new("你好 你好 [ni3 blorp3] {nei5 hot2} /hello/").unwrap();
+    /// let errors = entry.validate().unwrap_err();
+    /// assert_eq!(errors[0].syllable, "blorp3");
+    /// assert_eq!(errors[1].syllable, "hot2");
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<SyllableError>> {
+        let pinyin = self.pinyin.iter().flatten().map(Syllable::validate);
+        let jyutping = self.jyutping.iter().flatten().map(Syllable::validate);
+
+        let errors: Vec<SyllableError> = pinyin.chain(jyutping).filter_map(Result::err).collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The pinyin reading in IPA, or `None` if the entry has no pinyin.
+    ///
+    /// ```
+    /// # use cccedict::cedict_entry::*;
+    /// # use cccedict::ipa::ToneNotation;
+    /// let entry = CedictEntry::new("中文 中文 [Zhong1 wen2] {zung1 man4} /Chinese/").unwrap();
+    /// assert_eq!(
+    ///     entry.mandarin_ipa(ToneNotation::ChaoLetters),
+    ///     Some("ʈʂʊŋ˥ wən˧˥".to_string())
+    /// );
+    /// assert_eq!(
+    ///     entry.cantonese_ipa(ToneNotation::Numbers),
+    ///     Some("tsoŋ⁵⁵ mɐn²¹".to_string())
+    /// );
+    /// ```
+    pub fn mandarin_ipa(&self, notation: ToneNotation) -> Option<String> {
+        self.pinyin
+            .as_deref()
+            .map(|pinyin| pinyin.to_ipa_with(notation))
+    }
+
+    /// The pinyin reading as it is spoken, with tone sandhi applied, or `None` if the entry has
+    /// no pinyin. See `crate::sandhi`.
+    pub fn spoken_pinyin(&self) -> Option<Vec<Syllable<Pinyin>>> {
+        self.pinyin
+            .as_deref()
+            .map(|pinyin| sandhi::spoken(&self.simplified, pinyin))
+    }
+
+    /// The jyutping reading in IPA, or `None` if the entry has no jyutping.
+    pub fn cantonese_ipa(&self, notation: ToneNotation) -> Option<String> {
+        self.jyutping
+            .as_deref()
+            .map(|jyutping| jyutping.to_ipa_with(notation))
+    }
+}
+
+impl fmt::Display for CedictEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_entry(
+            f,
+            &self.traditional,
+            &self.simplified,
+            self.pinyin.as_deref(),
+            self.jyutping.as_deref(),
+            self.definitions.as_deref(),
+            self.comment.as_deref(),
+        )
+    }
+}
+
+/// Writes an entry as `trad simp [pin1 yin1] {jyut1 ping3} /def/def/ # comment`. Missing jyutping
+/// and comments are left out and missing definitions are written as `//`, all of which parse back
+/// to `None`.
+fn write_entry<P: fmt::Display, J: fmt::Display, D: AsRef<str>>(
+    f: &mut fmt::Formatter,
+    traditional: &str,
+    simplified: &str,
+    pinyin: Option<&[P]>,
+    jyutping: Option<&[J]>,
+    definitions: Option<&[D]>,
+    comment: Option<&str>,
+) -> fmt::Result {
+    fn write_syllables<S: fmt::Display>(f: &mut fmt::Formatter, syllables: &[S]) -> fmt::Result {
+        for (idx, syllable) in syllables.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", syllable)?;
+        }
+        Ok(())
+    }
+
+    write!(f, "{} {} [", traditional, simplified)?;
+    write_syllables(f, pinyin.unwrap_or(&[]))?;
+    write!(f, "]")?;
+
+    if let Some(jyutping) = jyutping {
+        write!(f, " {{")?;
+        write_syllables(f, jyutping)?;
+        write!(f, "}}")?;
+    }
+
+    write!(f, " /")?;
+    for definition in definitions.unwrap_or(&[]) {
+        write!(f, "{}/", definition.as_ref())?;
+    }
+    if definitions.is_none() {
+        write!(f, "/")?;
+    }
+
+    match comment {
+        Some("") => write!(f, " #"),
+        Some(comment) => write!(f, " # {}", comment),
+        None => Ok(()),
+    }
+}
+
+/// A `CedictEntryRef` is a `CedictEntry` that borrows its fields from the parsed line instead of
+/// allocating them, which is cheaper when scanning a whole dictionary:
+///
+/// ```
+/// use cccedict::cedict_entry::*;
+///
+/// let line = "你好嗎 你好吗 [ni3 hao3 ma5] {nei5 hou2 maa1} /how are you?/";
+/// let entry = CedictEntryRef::new(line).unwrap();
+///
+/// assert_eq!(entry.traditional, "你好嗎");
+/// assert_eq!(entry.pinyin.as_ref().unwrap()[0], SyllableRef::new("ni", "3"));
+/// assert_eq!(entry.definitions, Some(vec!["how are you?"]));
+/// assert_eq!(entry.to_owned(), CedictEntry::new(line).unwrap());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CedictEntryRef<'a> {
+    pub traditional: &'a str,
+    pub simplified: &'a str,
+    pub pinyin: Option<Vec<SyllableRef<'a, Pinyin>>>,
+    pub jyutping: Option<Vec<SyllableRef<'a, Jyutping>>>,
+    pub definitions: Option<Vec<&'a str>>,
+    pub comment: Option<&'a str>,
+}
+
+impl<'a> CedictEntryRef<'a> {
+    pub fn new(input: &'a str) -> Result<CedictEntryRef<'a>, CedictEntryError> {
+        match parsers::parse_line(input)? {
+            Some(entry) => Ok(entry),
+            None => Err(CedictEntryError::new(CedictEntryErrorKind::Empty, input, 0)),
+        }
+    }
+
+    /// Like `CedictEntryRef::new`, but returns `Ok(None)` for blank and comment-only lines
+    /// instead of an error.
+    pub fn parse_line(input: &'a str) -> Result<Option<CedictEntryRef<'a>>, CedictEntryError> {
+        parsers::parse_line(input)
+    }
+
+    pub fn to_owned(&self) -> CedictEntry {
+        fn to_syllables<R: Romanization>(syllables: &Vec<SyllableRef<R>>) -> Vec<Syllable<R>> {
+            syllables.iter().map(|s| s.to_owned()).collect()
+        }
+
+        CedictEntry {
+            traditional: self.traditional.to_string(),
+            simplified: self.simplified.to_string(),
+            pinyin: self.pinyin.as_ref().map(to_syllables),
+            jyutping: self.jyutping.as_ref().map(to_syllables),
+            definitions: self
+                .definitions
+                .as_ref()
+                .map(|defs| defs.iter().map(|def| def.to_string()).collect()),
+            comment: self.comment.map(|comment| comment.to_string()),
         }
     }
 }
 
-pub(self) mod parsers {
+impl<'a> fmt::Display for CedictEntryRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_entry(
+            f,
+            self.traditional,
+            self.simplified,
+            self.pinyin.as_deref(),
+            self.jyutping.as_deref(),
+            self.definitions.as_deref(),
+            self.comment,
+        )
+    }
+}
+
+impl<'a> From<CedictEntryRef<'a>> for CedictEntry {
+    fn from(entry: CedictEntryRef<'a>) -> Self {
+        entry.to_owned()
+    }
+}
+
+mod parsers {
     use super::*;
 
+    use nom::error::{Error, ErrorKind};
     use nom::{bytes, character, combinator, multi, sequence, IResult};
 
-    pub fn parse_line(i: &str) -> IResult<&str, Option<CedictEntry>> {
-        combinator::all_consuming(|i| {
-            let (i, entry) = combinator::opt(cedict_entry)(i)?;
-            let (i, _) = character::complete::space0(i)?;
-            let (i, _) = combinator::opt(comment)(i)?;
+    /// Parses a single line, returning `None` for blank and comment-only lines.
+    pub fn parse_line(line: &str) -> Result<Option<CedictEntryRef<'_>>, CedictEntryError> {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            return Ok(None);
+        }
 
-            Ok((i, entry))
-        })(i)
+        let (i, mut entry) = cedict_entry(line)?;
+        let i = i.trim_start_matches([' ', '\t']);
+
+        if i.is_empty() {
+            Ok(Some(entry))
+        } else if let Ok((_, (_, text))) = comment(i) {
+            entry.comment = Some(text.trim());
+            Ok(Some(entry))
+        } else {
+            Err(error_at(line, i, CedictEntryErrorKind::TrailingGarbage))
+        }
     }
 
-    fn cedict_entry(i: &str) -> IResult<&str, CedictEntry> {
-        let (i, traditional) = not_whitespace(i)?;
-        let (i, _) = character::complete::space1(i)?;
-        let (i, simplified) = not_whitespace(i)?;
-        let (i, _) = character::complete::space1(i)?;
-        let (i, pinyin) = pinyin(i)?;
-        let (i, _) = character::complete::space1(i)?;
-        let (i, jyutping) = combinator::opt(jyutping)(i)?;
-        let (i, _) = character::complete::space0(i)?;
-        let (i, definitions) = definitions(i)?;
+    fn cedict_entry(line: &str) -> Result<(&str, CedictEntryRef<'_>), CedictEntryError> {
+        use CedictEntryErrorKind::*;
+        let fail = |i: &str, kind| error_at(line, i, kind);
+
+        let (i, traditional) = not_whitespace(line).map_err(|_| fail(line, Traditional))?;
+        let (i, _) = space1(i).map_err(|_| fail(i, Simplified))?;
+        let (i, simplified) = not_whitespace(i).map_err(|_| fail(i, Simplified))?;
+        let (i, _) = space1(i).map_err(|_| fail(i, PinyinBrackets))?;
+        let (i, pinyin) = pinyin(i).map_err(|err| match pinyin_brackets(i) {
+            Ok(_) => fail(error_input(err, i), PinyinSyllable),
+            Err(_) => fail(i, PinyinBrackets),
+        })?;
+        if let Some(tone) = invalid_tone(pinyin.as_deref()) {
+            return Err(fail(tone, PinyinTone));
+        }
+        let (i, _) = space1(i).map_err(|_| fail(i, DefinitionSlashes))?;
+        let (i, jyutping) = if i.starts_with('{') {
+            let (i, jyutping) = jyutping(i).map_err(|err| match jyutping_braces(i) {
+                Ok(_) => fail(error_input(err, i), JyutpingSyllable),
+                Err(_) => fail(i, JyutpingBraces),
+            })?;
+            if let Some(tone) = invalid_tone(Some(&jyutping)) {
+                return Err(fail(tone, JyutpingTone));
+            }
+            (i, Some(jyutping).filter(|jyutping| !jyutping.is_empty()))
+        } else {
+            (i, None)
+        };
+        let (i, _) = space0(i).map_err(|_| fail(i, DefinitionSlashes))?;
+        let (rest, definitions) = definitions(i).map_err(|_| fail(i, DefinitionSlashes))?;
+
+        if definitions.is_none() && !rest.is_empty() && comment(rest).is_err() {
+            return Err(fail(rest, DefinitionSlashes));
+        }
 
         Ok((
-            i,
-            CedictEntry {
-                traditional: traditional.into(),
-                simplified: simplified.into(),
+            rest,
+            CedictEntryRef {
+                traditional,
+                simplified,
                 pinyin,
                 jyutping,
                 definitions,
+                comment: None,
             },
         ))
     }
 
+    /// Returns the first tone that isn't valid in `R`. Syllables without a tone are allowed.
+    fn invalid_tone<'a, R: Romanization>(
+        syllables: Option<&[SyllableRef<'a, R>]>,
+    ) -> Option<&'a str> {
+        syllables
+            .unwrap_or(&[])
+            .iter()
+            .find(|syllable| !syllable.tone.is_empty() && syllable.typed_tone().is_none())
+            .map(|syllable| syllable.tone)
+    }
+
+    /// Builds an error pointing at the start of `at`, which must be a slice of `line`.
+    fn error_at(line: &str, at: &str, kind: CedictEntryErrorKind) -> CedictEntryError {
+        CedictEntryError::new(kind, line, at.as_ptr() as usize - line.as_ptr() as usize)
+    }
+
+    /// The input a parser failed at. Incomplete errors carry no input, so they point at `input`,
+    /// the slice the parser was given, to keep the error's offset within the line.
+    fn error_input<'a>(err: nom::Err<Error<&'a str>>, input: &'a str) -> &'a str {
+        match err {
+            nom::Err::Error(err) | nom::Err::Failure(err) => err.input,
+            nom::Err::Incomplete(_) => input,
+        }
+    }
+
+    fn space0(i: &str) -> IResult<&str, &str> {
+        character::complete::space0(i)
+    }
+
+    fn space1(i: &str) -> IResult<&str, &str> {
+        character::complete::space1(i)
+    }
+
     fn comment(i: &str) -> IResult<&str, (&str, &str)> {
         sequence::tuple((
             bytes::complete::tag("#"),
@@ -98,65 +392,117 @@ pub(self) mod parsers {
         bytes::complete::is_not(" \t")(i)
     }
 
-    fn pinyin(i: &str) -> IResult<&str, Option<Vec<Syllable>>> {
-        let (rest, (_, pronunciations, _)) = sequence::tuple((
+    fn pinyin(i: &str) -> IResult<&str, Option<Vec<SyllableRef<'_, Pinyin>>>> {
+        let (rest, pronunciations) = pinyin_brackets(i)?;
+        let (_, syllables) = syllables(pronunciations)?;
+
+        Ok((
+            rest,
+            Some(syllables).filter(|syllables| !syllables.is_empty()),
+        ))
+    }
+
+    fn pinyin_brackets(i: &str) -> IResult<&str, &str> {
+        sequence::delimited(
             bytes::complete::tag("["),
-            combinator::opt(bytes::complete::is_not("]")),
+            // A `/` or `[` before the closing bracket means the bracket was never closed
+            combinator::map(combinator::opt(bytes::complete::is_not("]/[")), |p| {
+                p.unwrap_or("")
+            }),
             bytes::complete::tag("]"),
-        ))(i)?;
+        )(i)
+    }
 
-        if let Some(pronunciations) = pronunciations {
-            let (_, syllables) = syllables(pronunciations)?;
+    fn jyutping(i: &str) -> IResult<&str, Vec<SyllableRef<'_, Jyutping>>> {
+        let (rest, pronunciations) = jyutping_braces(i)?;
+        let (_, syllables) = syllables(pronunciations)?;
 
-            Ok((rest, Some(syllables)))
-        } else {
-            Ok((rest, None))
-        }
+        Ok((rest, syllables))
     }
 
-    fn jyutping(i: &str) -> IResult<&str, Vec<Syllable>> {
-        let (rest, pronunciations) = sequence::delimited(
+    fn jyutping_braces(i: &str) -> IResult<&str, &str> {
+        sequence::delimited(
             bytes::complete::tag("{"),
-            bytes::complete::is_not("}"),
+            bytes::complete::is_not("}/{"),
             bytes::complete::tag("}"),
-        )(i)?;
-
-        let (_, syllables) = syllables(pronunciations)?;
+        )(i)
+    }
 
-        Ok((rest, syllables))
+    /// takes a series of possibly undelimited syllables such as "ni3hao3" and returns a Vec of
+    /// Syllables. Fails if anything other than syllables, letters and punctuation is left over.
+    fn syllables<R: Romanization>(i: &str) -> IResult<&str, Vec<SyllableRef<'_, R>>> {
+        combinator::all_consuming(sequence::terminated(
+            multi::many0(syllable),
+            character::complete::multispace0,
+        ))(i)
     }
 
-    /// takes a series of possibly undelimited syllables such as "ni3hao3" and returns a Vec of Syllables
-    fn syllables(i: &str) -> IResult<&str, Vec<Syllable>> {
-        multi::many0(syllable)(i)
+    fn syllable<R: Romanization>(i: &str) -> IResult<&str, SyllableRef<'_, R>> {
+        let (i, _) = character::complete::multispace0(i)?;
+
+        if let Some(c) = i.chars().next().filter(|c| PUNCTUATION.contains(c)) {
+            let (punctuation, rest) = i.split_at(c.len_utf8());
+            return Ok((rest, SyllableRef::punctuation(punctuation)));
+        }
+
+        let (rest, (pronunciation, tone)) =
+            sequence::pair(romanization, character::complete::digit0)(i)?;
+
+        // A lone toneless capital, as in AA制, is a letter, but a lowercase `a` or `e` may be a
+        // syllable written without its tone
+        let is_letter = tone.is_empty()
+            && pronunciation.len() == 1
+            && (pronunciation.chars().all(|c| c.is_ascii_uppercase())
+                || !R::is_syllable(pronunciation));
+
+        if is_letter {
+            Ok((rest, SyllableRef::letter(pronunciation)))
+        } else {
+            Ok((rest, SyllableRef::new(pronunciation, tone)))
+        }
     }
 
-    fn syllable(i: &str) -> IResult<&str, Syllable> {
-        let (rest, (_, pronunciation, tone)) = sequence::tuple((
-            character::complete::space0,
-            character::complete::alpha1,
-            character::complete::digit0,
-        ))(i)?;
+    /// Punctuation that CC-CEDICT copies from the headword into the reading
+    const PUNCTUATION: &[char] = &['·', '・', ',', '，'];
+
+    /// Latin letters, plus `:` directly after a `u` to spell ü as `u:`
+    fn romanization(i: &str) -> IResult<&str, &str> {
+        let mut previous = None;
+        let end = i
+            .char_indices()
+            .find(|&(_, c)| {
+                let accepted = c.is_ascii_alphabetic()
+                    || (c == ':' && matches!(previous, Some('u') | Some('U')));
+                previous = Some(c);
+                !accepted
+            })
+            .map_or(i.len(), |(idx, _)| idx);
 
-        Ok((rest, Syllable::new(pronunciation, tone)))
+        if end == 0 {
+            Err(nom::Err::Error(Error::new(i, ErrorKind::Alpha)))
+        } else {
+            Ok((&i[end..], &i[..end]))
+        }
     }
 
-    fn definitions(i: &str) -> IResult<&str, Option<Vec<String>>> {
+    fn definitions(i: &str) -> IResult<&str, Option<Vec<&str>>> {
         if let Some(last_slash) = i.rfind('/') {
             let (defs, rest) = i.split_at(last_slash + 1);
 
-            let (_, untrimmed_defs) = sequence::delimited(
-                bytes::complete::tag("/"),
-                multi::separated_list0(bytes::complete::tag("/"), bytes::complete::is_not("/")),
-                bytes::complete::tag("/"),
-            )(defs)?;
+            let (inner, _) = bytes::complete::tag("/")(defs)?;
+            let inner = match inner.strip_suffix('/') {
+                Some(inner) => inner,
+                None => return Err(nom::Err::Error(Error::new(inner, ErrorKind::Tag))),
+            };
+
+            // Empty definitions such as the middle of `/a//b/` are kept so the entry is written
+            // back out unchanged
+            let trimmed: Vec<&str> = inner.split('/').map(|x| x.trim()).collect();
 
-            if untrimmed_defs.is_empty() {
+            if trimmed.iter().all(|x| x.is_empty()) {
                 return Ok((rest, None));
             }
 
-            let trimmed: Vec<String> = untrimmed_defs.iter().map(|x| x.trim().to_owned()).collect();
-
             Ok((rest, Some(trimmed)))
         } else {
             Ok((i, None))
@@ -183,6 +529,7 @@ pub(self) mod parsers {
                     "to search".into(),
                     "flip through a dictionary [colloquial]".into(),
                 ]),
+                comment: Some("adapted from cc-cedict".into()),
             };
 
             match CedictEntry::new(line) {
@@ -196,21 +543,123 @@ pub(self) mod parsers {
             let line = "hi";
             match CedictEntry::new(line) {
                 Ok(_) => panic!(),
-                Err(err) => assert_eq!(err.to_string(), "invalid cedict entry input"),
+                Err(err) => assert_eq!(
+                    err.to_string(),
+                    "invalid cedict entry input: missing simplified headword at column 3\nhi\n  ^"
+                ),
             };
 
             let line = "你好";
             match CedictEntry::new(line) {
                 Ok(_) => panic!(),
-                Err(err) => assert_eq!(err.to_string(), "invalid cedict entry input"),
+                Err(err) => assert_eq!(
+                    err.to_string(),
+                    "invalid cedict entry input: missing simplified headword at column 3\n你好\n    ^"
+                ),
             };
 
             let line = "抄字典 [chao1 zi4dian3] /to search / flip through a dictionary [colloquial]/ # adapted from cc-cedict";
             match CedictEntry::new(line) {
                 Ok(_) => panic!(),
-                Err(err) => assert_eq!(err.to_string(), "invalid cedict entry input"),
+                Err(err) => {
+                    assert_eq!(err.kind, CedictEntryErrorKind::PinyinBrackets);
+                    assert_eq!(err.column, 12);
+                    assert_eq!(err.byte_offset, 17);
+                    assert_eq!(err.snippet, "zi4dian3]");
+                }
             };
         }
+
+        #[test]
+        fn test_display_round_trip() {
+            let lines = [
+                "抄字典 抄字典 [chao1 zi4 dian3] {caau3 zi6 din2} /to search/flip through a dictionary [colloquial]/",
+                "以身作則 以身作则 [yi3 shen1 zuo4 ze2] /to set an example (idiom); to serve as a model/",
+                "𠌥 𠆿 [] {wu1} /(verb) to lean over; to stoop/",
+                "你好 你好 [ni3 hao3] //",
+                "你好 你好 [ni3 hao3] /hello//hi/",
+                "AA制 AA制 [A A zhi4] /to split the bill/",
+                "伊麗莎白·大衛 伊丽莎白·大卫 [Yi1 li4 sha1 bai2 · Da4 wei4] /Elizabeth David/",
+                "綠 绿 [lu:4] /green/",
+                "一世人 一世人 [yi1 shi4 ren2] {jat1 sai3 jan4} /one's entire life/ # adapted from cc-cedict",
+                "一世人 一世人 [yi1 shi4 ren2] {jat1 sai3 jan4} /one's entire life/ #",
+            ];
+
+            for line in lines.iter() {
+                let entry = CedictEntry::new(line).unwrap();
+                assert_eq!(&entry.to_string(), line);
+                assert_eq!(CedictEntry::new(&entry.to_string()).unwrap(), entry);
+                assert_eq!(&CedictEntryRef::new(line).unwrap().to_string(), line);
+            }
+        }
+
+        #[test]
+        fn test_display_normalizes_spacing() {
+            let entry = CedictEntry::new("你好  你好\t[ ni3hao3 ] { }  / hello /").unwrap();
+            assert_eq!(entry.to_string(), "你好 你好 [ni3 hao3] /hello/");
+        }
+
+        #[test]
+        fn test_new_with_invalid_syllables() {
+            let err = CedictEntry::new("你好 你好 [ni3 ha?o3] /hello/").unwrap_err();
+            assert_eq!(err.kind, CedictEntryErrorKind::PinyinSyllable);
+            assert_eq!(err.snippet, "?o3]");
+
+            let err = CedictEntry::new("你好 你好 [ni3 hao3] {nei5 hou2-} /hello/").unwrap_err();
+            assert_eq!(err.kind, CedictEntryErrorKind::JyutpingSyllable);
+            assert_eq!(err.column, 28);
+        }
+
+        #[test]
+        fn test_new_with_invalid_tones() {
+            let err = CedictEntry::new("你好 你好 [ni3 hao7] {nei5 hou2} /hello/").unwrap_err();
+            assert_eq!(err.kind, CedictEntryErrorKind::PinyinTone);
+            assert_eq!(err.snippet, "7]");
+
+            let err = CedictEntry::new("你好 你好 [ni3 hao3] {nei5 hou23} /hello/").unwrap_err();
+            assert_eq!(err.kind, CedictEntryErrorKind::JyutpingTone);
+            assert_eq!(err.snippet, "23}");
+
+            assert!(CedictEntry::new("一 一 [yi1] {jat7} /one/").is_ok());
+        }
+
+        #[test]
+        fn test_new_with_positioned_errors() {
+            let cases = [
+                ("", CedictEntryErrorKind::Empty, 1),
+                ("# just a comment", CedictEntryErrorKind::Empty, 1),
+                (
+                    "抄字典 抄字典 [chao1 zi4dian3 /to search/",
+                    CedictEntryErrorKind::PinyinBrackets,
+                    9,
+                ),
+                (
+                    "抄字典 抄字典 [chao1 zi4dian3] {caau3 zi6 din2 /to search/",
+                    CedictEntryErrorKind::JyutpingBraces,
+                    26,
+                ),
+                (
+                    "抄字典 抄字典 [chao1 zi4dian3] to search",
+                    CedictEntryErrorKind::DefinitionSlashes,
+                    26,
+                ),
+                (
+                    "抄字典 抄字典 [chao1 zi4dian3] /to search",
+                    CedictEntryErrorKind::DefinitionSlashes,
+                    26,
+                ),
+                (
+                    "抄字典 抄字典 [chao1 zi4dian3] /to search/ oops",
+                    CedictEntryErrorKind::TrailingGarbage,
+                    38,
+                ),
+            ];
+
+            for (line, kind, column) in cases.iter() {
+                let err = CedictEntry::new(line).unwrap_err();
+                assert_eq!((err.kind, err.column), (*kind, *column), "{}", line);
+            }
+        }
     }
 
     #[cfg(test)]
@@ -218,6 +667,15 @@ pub(self) mod parsers {
         use super::*;
         use crate::errors::BoxError;
 
+        #[test]
+        fn test_error_input_incomplete_stays_in_line() {
+            let line = "你好 你好 [ni3 hao3] /hello/";
+            let at = error_input(nom::Err::Incomplete(nom::Needed::Unknown), &line[14..]);
+            let error = error_at(line, at, CedictEntryErrorKind::PinyinSyllable);
+
+            assert_eq!(error.byte_offset, 14);
+        }
+
         #[test]
         fn test_not_whitespace() {
             assert_eq!(not_whitespace("你好 阿婆"), Ok((" 阿婆", "你好")));
@@ -238,10 +696,10 @@ pub(self) mod parsers {
                 Ok((
                     "",
                     Some(vec![
-                        "watch a movie".into(),
-                        "three goals".into(),
-                        "card".into(),
-                        "(deck of playing cards)".into(),
+                        "watch a movie",
+                        "three goals",
+                        "card",
+                        "(deck of playing cards)",
                     ])
                 ))
             )
@@ -254,10 +712,10 @@ pub(self) mod parsers {
                 Ok((
                     " # hi",
                     Some(vec![
-                        "watch a movie".into(),
-                        "three goals".into(),
-                        "card".into(),
-                        "(deck of playing cards)".into(),
+                        "watch a movie",
+                        "three goals",
+                        "card",
+                        "(deck of playing cards)",
                     ])
                 ))
             );
@@ -267,10 +725,10 @@ pub(self) mod parsers {
                 Ok((
                     "# hi",
                     Some(vec![
-                        "watch a movie".into(),
-                        "three goals".into(),
-                        "card".into(),
-                        "(deck of playing cards)".into(),
+                        "watch a movie",
+                        "three goals",
+                        "card",
+                        "(deck of playing cards)",
                     ])
                 ))
             )
@@ -282,6 +740,17 @@ pub(self) mod parsers {
             assert_eq!(definitions(""), Ok(("", None)));
         }
 
+        #[test]
+        fn test_parse_empty_definitions_are_kept() {
+            assert_eq!(definitions("/a//b/"), Ok(("", Some(vec!["a", "", "b"]))));
+            assert_eq!(definitions("/ /"), Ok(("", None)));
+        }
+
+        #[test]
+        fn test_parse_unclosed_definitions() {
+            assert!(definitions("/to search").is_err());
+        }
+
         #[test]
         fn test_parse_definitions_are_trimmed() {
             assert_eq!(
@@ -289,10 +758,10 @@ pub(self) mod parsers {
                 Ok((
                     "",
                     Some(vec![
-                        "watch a movie".into(),
-                        "three goals".into(),
-                        "card".into(),
-                        "(deck of playing cards)".into(),
+                        "watch a movie",
+                        "three goals",
+                        "card",
+                        "(deck of playing cards)",
                     ])
                 ))
             )
@@ -304,7 +773,10 @@ pub(self) mod parsers {
                 pinyin("[ni3 hao3]"),
                 Ok((
                     "",
-                    Some(vec![Syllable::new("ni", "3"), Syllable::new("hao", "3")])
+                    Some(vec![
+                        SyllableRef::new("ni", "3"),
+                        SyllableRef::new("hao", "3")
+                    ])
                 ))
             );
         }
@@ -316,9 +788,9 @@ pub(self) mod parsers {
                 Ok((
                     "",
                     Some(vec![
-                        Syllable::new("ni", "3"),
-                        Syllable::new("hao", "3"),
-                        Syllable::new("ma", "5")
+                        SyllableRef::new("ni", "3"),
+                        SyllableRef::new("hao", "3"),
+                        SyllableRef::new("ma", "5")
                     ])
                 ))
             );
@@ -328,9 +800,9 @@ pub(self) mod parsers {
                 Ok((
                     "",
                     Some(vec![
-                        Syllable::new("ni", "3"),
-                        Syllable::new("hao", "3"),
-                        Syllable::new("ma", "5")
+                        SyllableRef::new("ni", "3"),
+                        SyllableRef::new("hao", "3"),
+                        SyllableRef::new("ma", "5")
                     ])
                 ))
             );
@@ -343,22 +815,118 @@ pub(self) mod parsers {
 
         #[test]
         fn test_parse_pinyin_syllable() {
-            assert_eq!(syllable("ni3"), Ok(("", Syllable::new("ni", "3"))));
-            assert_eq!(syllable("hao3"), Ok(("", Syllable::new("hao", "3"))));
+            assert_eq!(
+                syllable::<Pinyin>("ni3"),
+                Ok(("", SyllableRef::new("ni", "3")))
+            );
+            assert_eq!(
+                syllable::<Pinyin>("hao3"),
+                Ok(("", SyllableRef::new("hao", "3")))
+            );
+        }
+
+        #[test]
+        fn test_parse_pinyin_with_umlauts() {
+            assert_eq!(
+                pinyin("[nu:3 er2]"),
+                Ok((
+                    "",
+                    Some(vec![
+                        SyllableRef::new("nu:", "3"),
+                        SyllableRef::new("er", "2")
+                    ])
+                ))
+            );
+            assert_eq!(
+                pinyin("[lu:e4]"),
+                Ok(("", Some(vec![SyllableRef::new("lu:e", "4")])))
+            );
+            assert!(pinyin("[li:4]").is_err());
+        }
+
+        #[test]
+        fn test_parse_pinyin_with_punctuation() {
+            assert_eq!(
+                pinyin("[Yi1 li4 sha1 bai2 · Da4 wei4]"),
+                Ok((
+                    "",
+                    Some(vec![
+                        SyllableRef::new("Yi", "1"),
+                        SyllableRef::new("li", "4"),
+                        SyllableRef::new("sha", "1"),
+                        SyllableRef::new("bai", "2"),
+                        SyllableRef::punctuation("·"),
+                        SyllableRef::new("Da", "4"),
+                        SyllableRef::new("wei", "4"),
+                    ])
+                ))
+            );
+            assert_eq!(
+                pinyin("[yi1 yan2 ji4 chu1 , si4 ma3 nan2 zhui1]")
+                    .unwrap()
+                    .1
+                    .unwrap()[4],
+                SyllableRef::punctuation(",")
+            );
+            assert_eq!(
+                pinyin("[chu1， si4]").unwrap().1.unwrap()[1],
+                SyllableRef::punctuation("，")
+            );
+        }
+
+        #[test]
+        fn test_parse_pinyin_with_letters() {
+            assert_eq!(
+                pinyin("[A A zhi4]"),
+                Ok((
+                    "",
+                    Some(vec![
+                        SyllableRef::letter("A"),
+                        SyllableRef::letter("A"),
+                        SyllableRef::new("zhi", "4"),
+                    ])
+                ))
+            );
+            assert_eq!(
+                pinyin("[xx5]"),
+                Ok(("", Some(vec![SyllableRef::new("xx", "5")])))
+            );
+        }
+
+        #[test]
+        fn test_parse_toneless_vowels_as_syllables() {
+            assert_eq!(
+                pinyin("[a]"),
+                Ok(("", Some(vec![SyllableRef::new("a", "")])))
+            );
+            assert_eq!(syllable::<Pinyin>("e"), Ok(("", SyllableRef::new("e", ""))));
+            assert_eq!(syllable::<Pinyin>("x"), Ok(("", SyllableRef::letter("x"))));
+        }
+
+        #[test]
+        fn test_parse_pinyin_rejects_leftover_input() {
+            assert_eq!(
+                syllables::<Pinyin>("ni3 hao3 ?"),
+                Err(nom::Err::Error(Error::new("?", ErrorKind::Eof)))
+            );
+            assert!(pinyin("[ni3 hao3 ?]").is_err());
         }
 
         #[test]
         fn test_parse_pinyin_syllable_without_tone() {
-            assert_eq!(syllable("ma"), Ok(("", Syllable::new("ma", ""))));
+            assert_eq!(
+                syllable::<Pinyin>("ma"),
+                Ok(("", SyllableRef::new("ma", "")))
+            );
         }
 
         #[test]
         fn test_parse_syllables() {
             assert_eq!(
-                syllables("ni3hao3"),
+                syllables::<Pinyin>("ni3hao3"),
                 Ok((
                     "",
-                    vec![Syllable::new("ni", "3"), Syllable::new("hao", "3")]
+                    vec![SyllableRef::new("ni", "3"), SyllableRef::new("hao", "3")]
                 ))
             );
         }
@@ -369,14 +937,14 @@ pub(self) mod parsers {
                 jyutping("{jat1 go3}"),
                 Ok((
                     "",
-                    vec![Syllable::new("jat", "1"), Syllable::new("go", "3")]
+                    vec![SyllableRef::new("jat", "1"), SyllableRef::new("go", "3")]
                 ))
             );
             assert_eq!(
                 jyutping("{jat1go3}"),
                 Ok((
                     "",
-                    vec![Syllable::new("jat", "1"), Syllable::new("go", "3")]
+                    vec![SyllableRef::new("jat", "1"), SyllableRef::new("go", "3")]
                 ))
             )
         }
@@ -396,23 +964,24 @@ pub(self) mod parsers {
                 cedict_entry(line),
                 Ok((
                     "",
-                    CedictEntry {
-                        traditional: "抄字典".into(),
-                        simplified: "抄字典".into(),
+                    CedictEntryRef {
+                        traditional: "抄字典",
+                        simplified: "抄字典",
                         pinyin: Some(vec![
-                            Syllable::new("chao", "1"),
-                            Syllable::new("zi", "4"),
-                            Syllable::new("dian", "3"),
+                            SyllableRef::new("chao", "1"),
+                            SyllableRef::new("zi", "4"),
+                            SyllableRef::new("dian", "3"),
                         ]),
                         jyutping: Some(vec![
-                            Syllable::new("caau", "3"),
-                            Syllable::new("zi", "6"),
-                            Syllable::new("din", "2"),
+                            SyllableRef::new("caau", "3"),
+                            SyllableRef::new("zi", "6"),
+                            SyllableRef::new("din", "2"),
                         ]),
                         definitions: Some(vec![
-                            "to search".into(),
-                            "flip through a dictionary [colloquial]".into()
-                        ])
+                            "to search",
+                            "flip through a dictionary [colloquial]"
+                        ]),
+                        comment: None,
                     }
                 ))
             )
@@ -426,19 +995,20 @@ pub(self) mod parsers {
                 cedict_entry(line),
                 Ok((
                     "",
-                    CedictEntry {
-                        traditional: "抄字典".into(),
-                        simplified: "抄字典".into(),
+                    CedictEntryRef {
+                        traditional: "抄字典",
+                        simplified: "抄字典",
                         pinyin: Some(vec![
-                            Syllable::new("chao", "1"),
-                            Syllable::new("zi", "4"),
-                            Syllable::new("dian", "3"),
+                            SyllableRef::new("chao", "1"),
+                            SyllableRef::new("zi", "4"),
+                            SyllableRef::new("dian", "3"),
                         ]),
                         jyutping: None,
                         definitions: Some(vec![
-                            "to search".into(),
-                            "flip through a dictionary [colloquial]".into()
-                        ])
+                            "to search",
+                            "flip through a dictionary [colloquial]"
+                        ]),
+                        comment: None,
                     }
                 ))
             )
@@ -451,19 +1021,20 @@ pub(self) mod parsers {
                 cedict_entry(line),
                 Ok((
                     " # adapted from cc-cedict",
-                    CedictEntry {
-                        traditional: "抄字典".into(),
-                        simplified: "抄字典".into(),
+                    CedictEntryRef {
+                        traditional: "抄字典",
+                        simplified: "抄字典",
                         pinyin: Some(vec![
-                            Syllable::new("chao", "1"),
-                            Syllable::new("zi", "4"),
-                            Syllable::new("dian", "3"),
+                            SyllableRef::new("chao", "1"),
+                            SyllableRef::new("zi", "4"),
+                            SyllableRef::new("dian", "3"),
                         ]),
                         jyutping: None,
                         definitions: Some(vec![
-                            "to search".into(),
-                            "flip through a dictionary [colloquial]".into()
-                        ])
+                            "to search",
+                            "flip through a dictionary [colloquial]"
+                        ]),
+                        comment: None,
                     }
                 ))
             )
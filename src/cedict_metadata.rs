@@ -0,0 +1,174 @@
+/*!
+A `CedictMetadata` holds the header block of a CC-CEDICT file: the `#! key=value` properties
+published by MDBG and any free-form comment lines that precede the first entry.
+
+# Usage:
+```
+use cccedict::cedict::Cedict;
+use std::str::FromStr;
+
+let cedict_text = "\
+## CC-CEDICT
+## Community maintained free Chinese-English dictionary.
+#! version=1
+#! subversion=0
+#! format=ts
+#! charset=UTF-8
+#! entries=1
+#! publisher=MDBG
+#! date=2021-03-04T10:21:04Z
+你好 你好 [ni3 hao3] /hello/";
+
+let cedict = Cedict::from_str(cedict_text).unwrap();
+let metadata = &cedict.metadata;
+
+assert_eq!(metadata.preamble, vec!["CC-CEDICT", "Community maintained free Chinese-English dictionary."]);
+assert_eq!(metadata.version(), Some("1"));
+assert_eq!(metadata.format(), Some("ts"));
+assert_eq!(metadata.entries(), Some("1"));
+assert_eq!(metadata.get("publisher"), Some("MDBG"));
+assert!(cedict.verify_entry_count().is_ok());
+```
+*/
+
+use std::fmt;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CedictMetadata {
+    /// Free-form comment lines before the first entry, with the leading `#` and a single space
+    /// stripped. Empty `#` lines are kept as empty strings.
+    pub preamble: Vec<String>,
+    /// `#! key=value` properties in the order they appear in the file.
+    pub properties: Vec<(String, String)>,
+}
+
+impl CedictMetadata {
+    /// Returns the value of the first `#!` property named `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.get("version")
+    }
+
+    pub fn subversion(&self) -> Option<&str> {
+        self.get("subversion")
+    }
+
+    pub fn format(&self) -> Option<&str> {
+        self.get("format")
+    }
+
+    pub fn charset(&self) -> Option<&str> {
+        self.get("charset")
+    }
+
+    pub fn entries(&self) -> Option<&str> {
+        self.get("entries")
+    }
+
+    pub fn publisher(&self) -> Option<&str> {
+        self.get("publisher")
+    }
+
+    pub fn license(&self) -> Option<&str> {
+        self.get("license")
+    }
+
+    pub fn date(&self) -> Option<&str> {
+        self.get("date")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.preamble.is_empty() && self.properties.is_empty()
+    }
+
+    /// Records `line` if it is a header comment, returning whether it was one.
+    pub(crate) fn push_header_line(&mut self, line: &str) -> bool {
+        let line = line.trim_end();
+
+        if let Some(property) = line.strip_prefix("#!") {
+            let property = property.trim();
+            let (key, value) = match property.find('=') {
+                Some(idx) => (&property[..idx], &property[idx + 1..]),
+                None => (property, ""),
+            };
+            self.properties
+                .push((key.trim().to_string(), value.trim().to_string()));
+            true
+        } else if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.strip_prefix(' ').unwrap_or(comment);
+            self.preamble.push(comment.to_string());
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Writes the header block back out as comment lines, the preamble followed by the properties.
+impl fmt::Display for CedictMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in &self.preamble {
+            if line.is_empty() {
+                writeln!(f, "#")?;
+            } else {
+                writeln!(f, "# {}", line)?;
+            }
+        }
+
+        for (key, value) in &self.properties {
+            writeln!(f, "#! {}={}", key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_header_line() {
+        let mut metadata = CedictMetadata::default();
+
+        assert!(metadata.push_header_line("# CC-Canto"));
+        assert!(metadata.push_header_line("#"));
+        assert!(metadata.push_header_line("#! entries=124244"));
+        assert!(
+            metadata.push_header_line("#! license=https://creativecommons.org/licenses/by-sa/4.0/")
+        );
+        assert!(!metadata.push_header_line("你好 你好 [ni3 hao3] /hello/"));
+
+        assert_eq!(metadata.preamble, vec!["CC-Canto", ""]);
+        assert_eq!(metadata.entries(), Some("124244"));
+        assert_eq!(
+            metadata.license(),
+            Some("https://creativecommons.org/licenses/by-sa/4.0/")
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let header = "# CC-Canto\n#\n#  indented\n#! version=1\n#! entries=3\n";
+        let mut metadata = CedictMetadata::default();
+        for line in header.lines() {
+            metadata.push_header_line(line);
+        }
+
+        assert_eq!(metadata.to_string(), header);
+    }
+
+    #[test]
+    fn test_push_header_line_without_value() {
+        let mut metadata = CedictMetadata::default();
+
+        assert!(metadata.push_header_line("#! experimental"));
+        assert_eq!(metadata.get("experimental"), Some(""));
+    }
+}
@@ -0,0 +1,165 @@
+/*!
+A `CedictReader` parses CC-CEDICT entries from a `BufRead` implementor one line at a time, so a
+dictionary can be filtered or streamed elsewhere without holding all of it in memory.
+
+# Usage:
+```
+use cccedict::cedict_reader::CedictReader;
+
+let cedict_text = "\
+#! entries=3
+你嘅 你嘅 [ni3 ge2] {nei5 ge3} /your's (spoken)/
+你地 你地 [ni3 di4 {nei5 dei6} /you guys; you all/
+你好嗎 你好吗 [ni3 hao3 ma5] {nei5 hou2 maa1} /how are you?/";
+
+let mut reader = CedictReader::new(cedict_text.as_bytes());
+
+let (line_number, entry) = reader.next().unwrap().unwrap();
+assert_eq!(line_number, 2);
+assert_eq!(entry.traditional, "你嘅");
+assert_eq!(reader.metadata().entries(), Some("3"));
+
+// Invalid lines are reported and reading continues with the next line
+assert!(reader.next().unwrap().is_err());
+
+let (line_number, entry) = reader.next().unwrap().unwrap();
+assert_eq!(line_number, 4);
+assert_eq!(entry.traditional, "你好嗎");
+assert!(reader.next().is_none());
+```
+*/
+
+use crate::cedict_entry::CedictEntry;
+use crate::cedict_metadata::CedictMetadata;
+use crate::errors::{CedictError, LineDiagnostic};
+use std::io::BufRead;
+
+#[derive(Debug)]
+pub struct CedictReader<R> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+    metadata: CedictMetadata,
+    in_header: bool,
+    done: bool,
+}
+
+impl<R: BufRead> CedictReader<R> {
+    pub fn new(reader: R) -> Self {
+        CedictReader {
+            reader,
+            buffer: String::new(),
+            line_number: 0,
+            metadata: CedictMetadata::default(),
+            in_header: true,
+            done: false,
+        }
+    }
+
+    /// The header comments read so far. The header is complete once the first entry has been
+    /// returned.
+    pub fn metadata(&self) -> &CedictMetadata {
+        &self.metadata
+    }
+
+    pub fn into_metadata(self) -> CedictMetadata {
+        self.metadata
+    }
+
+    /// The 1-based number of the last line read.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for CedictReader<R> {
+    type Item = Result<(usize, CedictEntry), CedictError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buffer.clear();
+
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line_number += 1;
+                    let line = self.buffer.trim_end_matches(['\n', '\r']);
+
+                    if self.in_header && self.metadata.push_header_line(line) {
+                        continue;
+                    }
+
+                    match CedictEntry::parse_line(line) {
+                        Ok(Some(entry)) => {
+                            self.in_header = false;
+                            return Some(Ok((self.line_number, entry)));
+                        }
+                        Ok(None) => continue,
+                        Err(error) => {
+                            return Some(Err(CedictError::InvalidLine(LineDiagnostic {
+                                line_number: self.line_number,
+                                line: line.to_string(),
+                                error,
+                            })))
+                        }
+                    }
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(CedictError::Io {
+                        line_number: self.line_number + 1,
+                        kind: err.kind(),
+                        message: err.to_string(),
+                    }));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_reads_fixture() {
+        let file = std::fs::File::open("fixtures/cccanto-test.txt").unwrap();
+        let mut reader = CedictReader::new(std::io::BufReader::new(file));
+
+        let line_numbers: Vec<usize> = (&mut reader).map(|r| r.unwrap().0).collect();
+        assert_eq!(line_numbers, vec![13, 14, 15]);
+        assert_eq!(reader.metadata().preamble[0], "CC-Canto");
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        let mut reader = CedictReader::new("你好 你好 [ni3 hao3] /hello/\r\n".as_bytes());
+
+        let (_, entry) = reader.next().unwrap().unwrap();
+        assert_eq!(entry.definitions, Some(vec!["hello".to_string()]));
+    }
+
+    #[test]
+    fn test_invalid_utf8_stops_reading() {
+        let input: &[u8] = b"\xff\xfe\n\xe4\xbd\xa0 \xe4\xbd\xa0 [ni3] /you/\n";
+        let mut reader = CedictReader::new(input);
+
+        match reader.next() {
+            Some(Err(CedictError::Io {
+                line_number, kind, ..
+            })) => {
+                assert_eq!(line_number, 1);
+                assert_eq!(kind, ErrorKind::InvalidData);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(reader.next().is_none());
+    }
+}
@@ -0,0 +1,263 @@
+/*!
+An `EnglishIndex` maps the words in entries' definitions to the entries that use them, so that a
+`Cedict` can be searched in English. Matches are ranked by `score`, which favours definitions that
+say exactly what was searched for.
+
+Each of an entry's definitions is a sense, and each sense is made of glosses separated by `;`. A
+gloss scores highest when it has the same words as the query, ignoring stopwords like "to" and
+"the", less when it contains the query as a phrase, and less again when it only shares some of the
+query's words or only mentions them in parentheses. Glosses with extra words and later senses score lower.
+
+# Usage:
+```
+use cccedict::cedict::Cedict;
+use std::str::FromStr;
+
+let cedict = Cedict::from_str("\
+电影院 电影院 [dian4 ying3 yuan4] /cinema; movie theater/
+电影 电影 [dian4 ying3] /movie; film/
+看 看 [kan4] /to see; to watch (a movie, a play etc)/").unwrap();
+
+let results = cedict.search_english("movie");
+let headwords: Vec<&str> = results
+    .iter()
+    .map(|(_, entry)| entry.simplified.as_str())
+    .collect();
+
+assert_eq!(headwords, vec!["电影", "电影院", "看"]);
+assert!(results[0].0 > results[1].0);
+```
+*/
+
+use crate::cedict_entry::CedictEntry;
+use std::collections::HashMap;
+
+/// Words too common to say much about a match. They still count, but far less than other words.
+pub const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on", "or",
+    "sb", "sth", "the", "to", "with",
+];
+
+const STOPWORD_WEIGHT: f64 = 0.1;
+const EXACT_SCORE: f64 = 1.0;
+const PHRASE_SCORE: f64 = 0.6;
+const PARTIAL_SCORE: f64 = 0.3;
+/// How much each word a gloss has beyond the query's takes off its score.
+const EXTRA_WORD_PENALTY: f64 = 0.1;
+/// How much each earlier sense takes off a gloss's score.
+const SENSE_PENALTY: f64 = 0.2;
+
+#[derive(Debug, Clone, Default)]
+pub struct EnglishIndex {
+    postings: HashMap<String, Vec<usize>>,
+}
+
+impl EnglishIndex {
+    pub fn new(entries: &[CedictEntry]) -> Self {
+        let mut index = EnglishIndex::default();
+        for (position, entry) in entries.iter().enumerate() {
+            index.push(position, entry);
+        }
+        index
+    }
+
+    /// Adds the words in the definitions of `entry`, found at `position` in the entry list.
+    pub fn push(&mut self, position: usize, entry: &CedictEntry) {
+        for definition in entry.definitions.iter().flatten() {
+            for word in tokenize(definition) {
+                let positions = self.postings.entry(word).or_default();
+                if positions.last() != Some(&position) {
+                    positions.push(position);
+                }
+            }
+        }
+    }
+
+    /// Positions of the entries that use any of the words in `query`, in file order. Stopwords
+    /// are only looked up when the query has nothing else.
+    pub fn candidates(&self, query: &str) -> Vec<usize> {
+        let words = tokenize(query);
+        let content: Vec<&String> = words.iter().filter(|word| !is_stopword(word)).collect();
+        let words: Vec<&String> = if content.is_empty() {
+            words.iter().collect()
+        } else {
+            content
+        };
+
+        let mut positions: Vec<usize> = words
+            .into_iter()
+            .filter_map(|word| self.postings.get(word.as_str()))
+            .flatten()
+            .copied()
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+}
+
+/// Splits `text` into lowercase words, dropping punctuation.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+pub fn is_stopword(word: &str) -> bool {
+    STOPWORDS.contains(&word)
+}
+
+/// How well `entry` matches `query`, from 0 for no match up to 1 for a first sense that says
+/// exactly what was searched for.
+pub fn score(query: &str, entry: &CedictEntry) -> f64 {
+    let query = &tokenize(query);
+    if query.is_empty() {
+        return 0.0;
+    }
+
+    entry
+        .definitions
+        .iter()
+        .flatten()
+        .enumerate()
+        .flat_map(|(sense, definition)| {
+            let sense_weight = 1.0 / (1.0 + SENSE_PENALTY * sense as f64);
+            definition
+                .split(';')
+                .map(move |gloss| sense_weight * score_gloss(query, gloss))
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Scores a single gloss. Words in parentheses, like the "movie" in "to show (a movie)", only
+/// qualify the gloss, so they can make a partial match but never an exact or phrase match.
+fn score_gloss(query: &[String], gloss: &str) -> f64 {
+    let words = tokenize(gloss);
+    let gloss = tokenize(&without_parentheticals(gloss));
+
+    let weight = |word: &String| {
+        if is_stopword(word) {
+            STOPWORD_WEIGHT
+        } else {
+            1.0
+        }
+    };
+
+    let total: f64 = query.iter().map(weight).sum();
+    let matched: f64 = query
+        .iter()
+        .filter(|word| words.contains(word))
+        .map(weight)
+        .sum();
+    if matched == 0.0 {
+        return 0.0;
+    }
+
+    let content = |words: &[String]| -> Vec<String> {
+        let content: Vec<String> = words
+            .iter()
+            .filter(|word| !is_stopword(word))
+            .cloned()
+            .collect();
+        if content.is_empty() {
+            words.to_vec()
+        } else {
+            content
+        }
+    };
+    let query_content = content(query);
+    let gloss_content = content(&gloss);
+
+    let base = if query_content == gloss_content {
+        EXACT_SCORE
+    } else if gloss.windows(query.len()).any(|window| window == query) {
+        PHRASE_SCORE
+    } else {
+        PARTIAL_SCORE * matched / total
+    };
+
+    let extra = gloss_content.len().saturating_sub(query_content.len());
+    base / (1.0 + EXTRA_WORD_PENALTY * extra as f64)
+}
+
+fn without_parentheticals(gloss: &str) -> String {
+    let mut depth = 0usize;
+    gloss
+        .chars()
+        .filter(|c| match c {
+            '(' => {
+                depth += 1;
+                false
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+            _ => depth == 0,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(line: &str) -> CedictEntry {
+        CedictEntry::new(line).unwrap()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("to watch (a movie, a play etc)"),
+            vec!["to", "watch", "a", "movie", "a", "play", "etc"]
+        );
+        assert_eq!(tokenize("CL:部[bu4]"), vec!["cl", "部", "bu4"]);
+    }
+
+    #[test]
+    fn test_score_ranking() {
+        let exact = entry("走 走 [zou3] /to walk/");
+        let phrase = entry("散步 散步 [san4 bu4] /to take a walk/");
+        let later_sense = entry("步 步 [bu4] /a step/to walk/");
+        let partial = entry("散步 散步 [san4 bu4] /to stroll (for a walk)/");
+
+        assert_eq!(score("walk", &exact), 1.0);
+        assert_eq!(score("to walk", &exact), 1.0);
+        assert!(score("walk", &exact) > score("walk", &later_sense));
+        assert!(score("walk", &later_sense) > score("walk", &phrase));
+        assert!(score("walk", &phrase) > score("walk", &partial));
+        assert_eq!(score("run", &exact), 0.0);
+    }
+
+    #[test]
+    fn test_stopwords_are_deweighted() {
+        let see = entry("看 看 [kan4] /to see/");
+        let see_off = entry("送行 送行 [song4 xing2] /to see sb off/");
+
+        assert_eq!(score("see", &see), score("to see", &see));
+        assert!(score("see", &see) > score("to", &see));
+        assert!(score("to", &see) > 0.0);
+        // "sb" is a stopword, so only "off" counts as an extra word
+        assert_eq!(
+            score("see", &see_off),
+            PHRASE_SCORE / (1.0 + EXTRA_WORD_PENALTY)
+        );
+    }
+
+    #[test]
+    fn test_candidates() {
+        let entries = vec![
+            entry("电影 电影 [dian4 ying3] /movie; film/"),
+            entry("走 走 [zou3] /to walk/"),
+            entry("看 看 [kan4] /to see; to watch (a movie)/"),
+        ];
+        let index = EnglishIndex::new(&entries);
+
+        assert_eq!(index.candidates("Movie"), vec![0, 2]);
+        assert_eq!(index.candidates("to walk"), vec![1]);
+        assert_eq!(index.candidates("to"), vec![1, 2]);
+        assert!(index.candidates("bicycle").is_empty());
+    }
+}
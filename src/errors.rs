@@ -1,24 +1,345 @@
-use std::{error, fmt};
+use std::{error, fmt, io};
 
 pub type BoxError = std::boxed::Box<dyn std::error::Error + std::marker::Send + std::marker::Sync>;
 
+/// The part of a CC-CEDICT line that failed to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CedictEntryErrorKind {
+    /// The line is blank or only contains a comment
+    Empty,
+    Traditional,
+    Simplified,
+    PinyinBrackets,
+    /// Something inside the pinyin brackets that isn't a syllable, letter or punctuation
+    PinyinSyllable,
+    /// A pinyin tone other than `1`–`5`
+    PinyinTone,
+    JyutpingBraces,
+    /// Something inside the jyutping braces that isn't a syllable, letter or punctuation
+    JyutpingSyllable,
+    /// A jyutping tone other than `1`–`9`
+    JyutpingTone,
+    DefinitionSlashes,
+    TrailingGarbage,
+}
+
+impl fmt::Display for CedictEntryErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            CedictEntryErrorKind::Empty => "line contains no entry",
+            CedictEntryErrorKind::Traditional => "missing traditional headword",
+            CedictEntryErrorKind::Simplified => "missing simplified headword",
+            CedictEntryErrorKind::PinyinBrackets => "missing or unclosed pinyin brackets `[...]`",
+            CedictEntryErrorKind::PinyinSyllable => "invalid pinyin syllable",
+            CedictEntryErrorKind::PinyinTone => "invalid pinyin tone",
+            CedictEntryErrorKind::JyutpingBraces => "unclosed jyutping braces `{...}`",
+            CedictEntryErrorKind::JyutpingSyllable => "invalid jyutping syllable",
+            CedictEntryErrorKind::JyutpingTone => "invalid jyutping tone",
+            CedictEntryErrorKind::DefinitionSlashes => {
+                "missing or unclosed definition slashes `/.../`"
+            }
+            CedictEntryErrorKind::TrailingGarbage => "unexpected input after definitions",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+/// Describes where and why a line failed to parse as a `CedictEntry`.
+///
+/// Its `Display` implementation renders the line with a caret under the problem:
+///
+/// ```
+/// use cccedict::cedict_entry::CedictEntry;
+/// use cccedict::errors::CedictEntryErrorKind;
+///
+/// let err = CedictEntry::new("你好 你好 [ni3 hao3 /hello/").unwrap_err();
+///
+/// assert_eq!(err.kind, CedictEntryErrorKind::PinyinBrackets);
+/// assert_eq!(err.column, 7);
+/// assert_eq!(err.to_string(), "\
+/// invalid cedict entry input: missing or unclosed pinyin brackets `[...]` at column 7 near \"[ni3\"
+/// 你好 你好 [ni3 hao3 /hello/
+///           ^");
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CedictEntryError;
+pub struct CedictEntryError {
+    pub kind: CedictEntryErrorKind,
+    /// The full line that failed to parse
+    pub line: String,
+    /// Byte offset of the problem within `line`
+    pub byte_offset: usize,
+    /// 1-based character column of the problem within `line`
+    pub column: usize,
+    /// The input at the problem, up to the next whitespace. Empty at the end of the line.
+    pub snippet: String,
+}
+
+impl CedictEntryError {
+    pub(crate) fn new(kind: CedictEntryErrorKind, line: &str, byte_offset: usize) -> Self {
+        let (prefix, rest) = line.split_at(byte_offset);
+        let snippet = rest
+            .chars()
+            .take_while(|c| !c.is_whitespace())
+            .take(16)
+            .collect();
+
+        CedictEntryError {
+            kind,
+            line: line.to_string(),
+            byte_offset,
+            column: prefix.chars().count() + 1,
+            snippet,
+        }
+    }
+}
 
 impl fmt::Display for CedictEntryError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "invalid cedict entry input")
+        write!(
+            f,
+            "invalid cedict entry input: {} at column {}",
+            self.kind, self.column
+        )?;
+        if !self.snippet.is_empty() {
+            write!(f, " near {:?}", self.snippet)?;
+        }
+
+        // Tabs are kept so the caret lines up regardless of the terminal's tab width
+        let padding: String = self.line[..self.byte_offset]
+            .chars()
+            .map(|c| match c {
+                '\t' => "\t".to_string(),
+                c => " ".repeat(display_width(c)),
+            })
+            .collect();
+
+        write!(f, "\n{}\n{}^", self.line, padding)
+    }
+}
+
+/// The number of terminal columns `c` occupies: two for East Asian wide characters, one otherwise.
+fn display_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
     }
 }
 
 impl error::Error for CedictEntryError {}
 
+/// A tone that is not valid for the romanization it was parsed as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToneError(pub String);
+
+impl fmt::Display for ToneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid tone {:?}", self.0)
+    }
+}
+
+impl error::Error for ToneError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyllableErrorKind {
+    /// Not a syllable in the pinyin inventory
+    UnknownPinyin,
+    /// A pinyin syllable that can't carry its tone, e.g. erhua `r` with a full tone
+    ImpossiblePinyinTone,
+    /// Not a syllable in the jyutping inventory
+    UnknownJyutping,
+    /// A jyutping syllable that can't carry its tone, e.g. a checked syllable ending in -p, -t or
+    /// -k with a non-entering tone
+    ImpossibleJyutpingTone,
+}
+
+/// A syllable that is well-formed but not a real syllable of its romanization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyllableError {
+    pub kind: SyllableErrorKind,
+    /// The syllable as written, including its tone
+    pub syllable: String,
+}
+
+impl fmt::Display for SyllableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            SyllableErrorKind::UnknownPinyin => {
+                write!(f, "{:?} is not a pinyin syllable", self.syllable)
+            }
+            SyllableErrorKind::ImpossiblePinyinTone => {
+                write!(f, "{:?} has an impossible pinyin tone", self.syllable)
+            }
+            SyllableErrorKind::UnknownJyutping => {
+                write!(f, "{:?} is not a jyutping syllable", self.syllable)
+            }
+            SyllableErrorKind::ImpossibleJyutpingTone => {
+                write!(f, "{:?} has an impossible jyutping tone", self.syllable)
+            }
+        }
+    }
+}
+
+impl error::Error for SyllableError {}
+
+/// Pinyin input that could not be split into syllables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinyinInputError {
+    pub input: String,
+    /// Byte offset of the character or syllable that could not be parsed
+    pub byte_offset: usize,
+}
+
+impl PinyinInputError {
+    pub(crate) fn new(input: &str, byte_offset: usize) -> Self {
+        PinyinInputError {
+            input: input.to_string(),
+            byte_offset,
+        }
+    }
+}
+
+impl fmt::Display for PinyinInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid pinyin input at byte {} of {:?}",
+            self.byte_offset, self.input
+        )
+    }
+}
+
+impl error::Error for PinyinInputError {}
+
+/// Zhuyin input that could not be read as pinyin syllables.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CedictError;
+pub struct ZhuyinInputError {
+    pub input: String,
+    /// Byte offset of the syllable that could not be parsed
+    pub byte_offset: usize,
+}
+
+impl ZhuyinInputError {
+    pub(crate) fn new(input: &str, byte_offset: usize) -> Self {
+        ZhuyinInputError {
+            input: input.to_string(),
+            byte_offset,
+        }
+    }
+}
+
+impl fmt::Display for ZhuyinInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid zhuyin input at byte {} of {:?}",
+            self.byte_offset, self.input
+        )
+    }
+}
+
+impl error::Error for ZhuyinInputError {}
+
+/// Yale input that could not be read as jyutping syllables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YaleInputError {
+    pub input: String,
+    /// Byte offset of the syllable that could not be parsed
+    pub byte_offset: usize,
+}
+
+impl YaleInputError {
+    pub(crate) fn new(input: &str, byte_offset: usize) -> Self {
+        YaleInputError {
+            input: input.to_string(),
+            byte_offset,
+        }
+    }
+}
+
+impl fmt::Display for YaleInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid yale input at byte {} of {:?}",
+            self.byte_offset, self.input
+        )
+    }
+}
+
+impl error::Error for YaleInputError {}
+
+/// A line of CC-CEDICT input that could not be parsed as an entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineDiagnostic {
+    /// 1-based line number within the input
+    pub line_number: usize,
+    pub line: String,
+    pub error: CedictEntryError,
+}
+
+impl fmt::Display for LineDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {:?}",
+            self.line_number, self.error, self.line
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CedictError {
+    /// A line that is neither blank, a comment, nor a valid entry.
+    InvalidLine(LineDiagnostic),
+    /// The `#! entries=` header does not match the number of entries that were parsed.
+    EntryCountMismatch { declared: usize, parsed: usize },
+    /// The `#! entries=` header is not a number.
+    InvalidEntryCount(String),
+    /// Reading the input failed, for example because it is not valid UTF-8.
+    Io {
+        line_number: usize,
+        kind: io::ErrorKind,
+        message: String,
+    },
+}
 
 impl fmt::Display for CedictError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "invalid cedict input")
+        match self {
+            CedictError::InvalidLine(diagnostic) => {
+                write!(f, "invalid cedict input: {}", diagnostic)
+            }
+            CedictError::EntryCountMismatch { declared, parsed } => write!(
+                f,
+                "invalid cedict input: header declares {} entries but {} were parsed",
+                declared, parsed
+            ),
+            CedictError::InvalidEntryCount(count) => write!(
+                f,
+                "invalid cedict input: header entry count {:?} is not a number",
+                count
+            ),
+            CedictError::Io {
+                line_number,
+                message,
+                ..
+            } => write!(
+                f,
+                "failed to read cedict input at line {}: {}",
+                line_number, message
+            ),
+        }
     }
 }
 
@@ -0,0 +1,342 @@
+/*!
+Fuzzy reading search, for learners who mix up sounds like `zh` and `z` or `-n` and `-ng`, or who
+mistype a letter. `FuzzyOptions` lists the sounds that count as the same, its `Confusion`s, and how
+many edits are allowed on top of them. A `Cedict` can then be searched with
+`Cedict::fuzzy_search_pinyin` or `Cedict::fuzzy_search_jyutping`.
+
+Queries are compared to readings syllable by syllable, so their syllables must be separated by
+spaces or tone numbers, as in `zong1 guo2` or `zong1guo2`. Each syllable first has its confused
+initial and ending replaced, then costs one edit for every letter that still differs and one more
+if the query gives it a different tone.
+
+# Usage:
+```
+use cccedict::cedict::Cedict;
+use cccedict::fuzzy::{Confusion, FuzzyOptions};
+use std::str::FromStr;
+
+let cedict = Cedict::from_str("\
+中国 中国 [Zhong1 guo2] {zung1 gwok3} /China/
+你好 你好 [ni3 hao3] {nei5 hou2} /hello/").unwrap();
+
+let results = cedict.fuzzy_search_pinyin("zong1 guo2", &FuzzyOptions::pinyin());
+assert_eq!(results[0].1.simplified, "中国");
+
+let results = cedict.fuzzy_search_jyutping("lei5 hou2", &FuzzyOptions::jyutping());
+assert_eq!(results[0], (0, &cedict.entries[1]));
+
+// Without the n/l confusion, "lei" is one edit away from "nei"
+let options = FuzzyOptions {
+    confusions: vec![Confusion::initial("ng", "")],
+    ..FuzzyOptions::jyutping()
+};
+assert_eq!(cedict.fuzzy_search_jyutping("lei5 hou2", &options)[0].0, 1);
+```
+*/
+
+use crate::reading_index;
+use crate::syllable::{Romanization, Syllable};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Two spellings that learners mix up and that should match each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confusion {
+    /// Initials, e.g. `zh` and `z`. Either can be empty, for syllables without an initial.
+    Initial(String, String),
+    /// The ends of finals, e.g. `n` and `ng`.
+    Ending(String, String),
+}
+
+impl Confusion {
+    pub fn initial(a: &str, b: &str) -> Self {
+        Confusion::Initial(a.to_string(), b.to_string())
+    }
+
+    pub fn ending(a: &str, b: &str) -> Self {
+        Confusion::Ending(a.to_string(), b.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyOptions {
+    pub confusions: Vec<Confusion>,
+    /// The most edits allowed in a single syllable once confusions are accounted for
+    pub max_edits_per_syllable: usize,
+    /// The most edits allowed across the whole reading
+    pub max_edits: usize,
+}
+
+impl FuzzyOptions {
+    /// zh/z, ch/c, sh/s, n/l and -n/-ng, with up to one edit.
+    pub fn pinyin() -> Self {
+        FuzzyOptions {
+            confusions: vec![
+                Confusion::initial("z", "zh"),
+                Confusion::initial("c", "ch"),
+                Confusion::initial("s", "sh"),
+                Confusion::initial("n", "l"),
+                Confusion::ending("n", "ng"),
+            ],
+            max_edits_per_syllable: 1,
+            max_edits: 1,
+        }
+    }
+
+    /// n/l and ng/no initial, with up to one edit.
+    pub fn jyutping() -> Self {
+        FuzzyOptions {
+            confusions: vec![Confusion::initial("n", "l"), Confusion::initial("ng", "")],
+            max_edits_per_syllable: 1,
+            max_edits: 1,
+        }
+    }
+}
+
+/// A query prepared for fuzzy matching against many readings, so that it is only parsed once.
+/// Dictionaries only use a few thousand distinct syllables, so the canonical spelling of each one
+/// is worked out once and remembered.
+#[derive(Debug, Clone)]
+pub struct FuzzyQuery<'a, R> {
+    /// Each syllable's canonical letters and tone number, if it was given one
+    syllables: Vec<(Vec<char>, Option<char>)>,
+    options: &'a FuzzyOptions,
+    canonical: RefCell<HashMap<String, Vec<char>>>,
+    romanization: PhantomData<R>,
+}
+
+impl<'a, R: Romanization> FuzzyQuery<'a, R> {
+    pub fn new(query: &str, options: &'a FuzzyOptions) -> Self {
+        let syllables = query_syllables(query)
+            .into_iter()
+            .map(|(letters, digit)| (canonical::<R>(&letters, options).chars().collect(), digit))
+            .collect();
+
+        FuzzyQuery {
+            syllables,
+            options,
+            canonical: RefCell::default(),
+            romanization: PhantomData,
+        }
+    }
+
+    /// How many edits `reading` is from the query, or `None` if it is further than the options
+    /// allow or has a different number of syllables.
+    pub fn distance(&self, reading: &[Syllable<R>]) -> Option<usize> {
+        if self.syllables.is_empty()
+            || reading_index::spoken(reading).count() != self.syllables.len()
+        {
+            return None;
+        }
+
+        let mut cache = self.canonical.borrow_mut();
+        let mut total = 0;
+        for ((letters, digit), syllable) in
+            self.syllables.iter().zip(reading_index::spoken(reading))
+        {
+            let pronunciation = &syllable.pronunciation;
+            let spelling = match cache.get(pronunciation) {
+                Some(spelling) => spelling,
+                None => {
+                    let spelling = reading_index::normalize(pronunciation);
+                    let spelling = canonical::<R>(&spelling, self.options).chars().collect();
+                    cache.entry(pronunciation.clone()).or_insert(spelling)
+                }
+            };
+            if letters.len().abs_diff(spelling.len()) > self.options.max_edits_per_syllable {
+                return None;
+            }
+
+            let mut edits = levenshtein(letters, spelling);
+            if digit.is_some_and(|digit| !reading_index::tones_agree::<R>(digit, &syllable.tone)) {
+                edits += 1;
+            }
+
+            total += edits;
+            if edits > self.options.max_edits_per_syllable || total > self.options.max_edits {
+                return None;
+            }
+        }
+
+        Some(total)
+    }
+}
+
+/// How many edits `reading` is from `query` under `options`, or `None` if it is further than
+/// `options` allows or has a different number of syllables. Use a `FuzzyQuery` to compare one
+/// query with many readings.
+pub fn distance<R: Romanization>(
+    query: &str,
+    reading: &[Syllable<R>],
+    options: &FuzzyOptions,
+) -> Option<usize> {
+    FuzzyQuery::new(query, options).distance(reading)
+}
+
+/// Splits `query` into normalized letters and optional tone numbers, one per syllable.
+fn query_syllables(query: &str) -> Vec<(String, Option<char>)> {
+    let mut syllables = vec![];
+    let mut letters = String::new();
+
+    for c in reading_index::normalize(query).chars() {
+        if c.is_ascii_digit() {
+            syllables.push((std::mem::take(&mut letters), Some(c)));
+        } else if c.is_alphabetic() {
+            letters.push(c);
+        } else if !letters.is_empty() {
+            syllables.push((std::mem::take(&mut letters), None));
+        }
+    }
+    if !letters.is_empty() {
+        syllables.push((letters, None));
+    }
+
+    syllables
+}
+
+/// Spells a normalized syllable with each confused initial and ending replaced by the first of
+/// its pair, so that confused spellings come out the same.
+fn canonical<R: Romanization>(syllable: &str, options: &FuzzyOptions) -> String {
+    let initial = R::INITIALS
+        .iter()
+        .find(|initial| syllable.starts_with(*initial))
+        .copied()
+        .unwrap_or("");
+    let mut initial = initial.to_string();
+    let mut rest = syllable[initial.len()..].to_string();
+
+    for confusion in &options.confusions {
+        match confusion {
+            Confusion::Initial(a, b) if initial == *b => initial = a.clone(),
+            Confusion::Ending(a, b) if rest.ends_with(b.as_str()) => {
+                rest.truncate(rest.len() - b.len());
+                rest.push_str(a);
+            }
+            _ => {}
+        }
+    }
+
+    initial + &rest
+}
+
+/// Edit distance between `a` and `b`, keeping a single row of the table. Syllables are short, so
+/// the row normally fits on the stack.
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut stack = [0; 16];
+    let mut heap = vec![];
+    let row: &mut [usize] = if b.len() < stack.len() {
+        &mut stack[..=b.len()]
+    } else {
+        heap.resize(b.len() + 1, 0);
+        &mut heap
+    };
+    for (j, cell) in row.iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for (i, a_char) in a.iter().enumerate() {
+        // The cell diagonally up and to the left of the one being filled in
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let above = row[j + 1];
+            let substitution = diagonal + usize::from(a_char != b_char);
+            row[j + 1] = substitution.min(above + 1).min(row[j] + 1);
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syllable::{Jyutping, JyutpingSyllable, Pinyin, PinyinSyllable};
+
+    #[test]
+    fn test_levenshtein() {
+        let levenshtein = |a: &str, b: &str| {
+            levenshtein(
+                &a.chars().collect::<Vec<_>>(),
+                &b.chars().collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(levenshtein("zong", "zong"), 0);
+        assert_eq!(levenshtein("zong", "zon"), 1);
+        assert_eq!(levenshtein("hao", "hou"), 2);
+        assert_eq!(levenshtein("", "lü"), 2);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("abcdefghijklmnopq", "abcdefghijklmnopqrs"), 2);
+    }
+
+    #[test]
+    fn test_canonical() {
+        let pinyin = FuzzyOptions::pinyin();
+        assert_eq!(canonical::<Pinyin>("zhong", &pinyin), "zon");
+        assert_eq!(canonical::<Pinyin>("zon", &pinyin), "zon");
+        assert_eq!(canonical::<Pinyin>("lü", &pinyin), "nü");
+        assert_eq!(canonical::<Pinyin>("shang", &pinyin), "san");
+
+        let jyutping = FuzzyOptions::jyutping();
+        assert_eq!(canonical::<Jyutping>("ngo", &jyutping), "ngo");
+        assert_eq!(canonical::<Jyutping>("o", &jyutping), "ngo");
+        assert_eq!(canonical::<Jyutping>("lei", &jyutping), "nei");
+    }
+
+    #[test]
+    fn test_query_syllables() {
+        assert_eq!(
+            query_syllables("Zong1guo2 ren"),
+            vec![
+                ("zong".to_string(), Some('1')),
+                ("guo".to_string(), Some('2')),
+                ("ren".to_string(), None),
+            ]
+        );
+        assert_eq!(query_syllables("lv4"), vec![("lü".to_string(), Some('4'))]);
+    }
+
+    #[test]
+    fn test_fuzzy_query_is_reused() {
+        let options = FuzzyOptions::pinyin();
+        let query = FuzzyQuery::<Pinyin>::new("zong1 guo2", &options);
+
+        let zhongguo = vec![
+            PinyinSyllable::new("Zhong", "1"),
+            PinyinSyllable::new("guo", "2"),
+        ];
+        let zhong = vec![PinyinSyllable::new("zhong", "1")];
+        assert_eq!(query.distance(&zhongguo), Some(0));
+        assert_eq!(query.distance(&zhong), None);
+        assert_eq!(query.distance(&zhongguo), Some(0));
+    }
+
+    #[test]
+    fn test_distance() {
+        let options = FuzzyOptions::pinyin();
+        let zhongguo = vec![
+            PinyinSyllable::new("Zhong", "1"),
+            PinyinSyllable::new("guo", "2"),
+        ];
+
+        assert_eq!(distance("zhong1 guo2", &zhongguo, &options), Some(0));
+        assert_eq!(distance("zong1 guo2", &zhongguo, &options), Some(0));
+        assert_eq!(distance("zon guo", &zhongguo, &options), Some(0));
+        assert_eq!(distance("zong1 guo3", &zhongguo, &options), Some(1));
+        assert_eq!(distance("zong1 gou3", &zhongguo, &options), None);
+        assert_eq!(distance("zong1", &zhongguo, &options), None);
+
+        let strict = FuzzyOptions {
+            max_edits: 0,
+            ..FuzzyOptions::pinyin()
+        };
+        assert_eq!(distance("zong1 guo3", &zhongguo, &strict), None);
+
+        let ngo = vec![JyutpingSyllable::new("ngo", "5")];
+        assert_eq!(distance("o5", &ngo, &FuzzyOptions::jyutping()), Some(0));
+        assert_eq!(distance("o5", &ngo, &FuzzyOptions::pinyin()), None);
+    }
+}
@@ -0,0 +1,78 @@
+/*!
+A `HeadwordIndex` maps traditional and simplified headwords to the positions of their entries, so
+that a `Cedict` can look entries up without scanning all of them. `Cedict` builds one when it is
+loaded and adds to it as entries are parsed; see `Cedict::lookup`.
+
+# Usage:
+```
+use cccedict::cedict_entry::CedictEntry;
+use cccedict::index::HeadwordIndex;
+
+let entries = vec![
+    CedictEntry::new("行 行 [hang2] /row; line/").unwrap(),
+    CedictEntry::new("銀行 银行 [yin2 hang2] /bank/").unwrap(),
+    CedictEntry::new("行 行 [xing2] /to walk; to go/").unwrap(),
+];
+let index = HeadwordIndex::new(&entries);
+
+assert_eq!(index.traditional("行"), &[0, 2]);
+assert_eq!(index.simplified("银行"), &[1]);
+assert!(index.traditional("银行").is_empty());
+```
+*/
+
+use crate::cedict_entry::CedictEntry;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct HeadwordIndex {
+    traditional: HashMap<String, Vec<usize>>,
+    simplified: HashMap<String, Vec<usize>>,
+}
+
+impl HeadwordIndex {
+    pub fn new(entries: &[CedictEntry]) -> Self {
+        let mut index = HeadwordIndex::default();
+        for (position, entry) in entries.iter().enumerate() {
+            index.push(position, entry);
+        }
+        index
+    }
+
+    /// Adds `entry`, found at `position` in the entry list. Positions should be pushed in
+    /// increasing order so that lookups return them in file order.
+    pub fn push(&mut self, position: usize, entry: &CedictEntry) {
+        self.traditional
+            .entry(entry.traditional.clone())
+            .or_default()
+            .push(position);
+        self.simplified
+            .entry(entry.simplified.clone())
+            .or_default()
+            .push(position);
+    }
+
+    /// Positions of the entries whose traditional headword is `headword`, in file order.
+    pub fn traditional(&self, headword: &str) -> &[usize] {
+        self.traditional.get(headword).map_or(&[], Vec::as_slice)
+    }
+
+    /// Positions of the entries whose simplified headword is `headword`, in file order.
+    pub fn simplified(&self, headword: &str) -> &[usize] {
+        self.simplified.get(headword).map_or(&[], Vec::as_slice)
+    }
+
+    /// Positions of the entries with `headword` in either script, in file order and without
+    /// duplicates.
+    pub fn any(&self, headword: &str) -> Vec<usize> {
+        let mut positions: Vec<usize> = self
+            .traditional(headword)
+            .iter()
+            .chain(self.simplified(headword))
+            .copied()
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+}
@@ -0,0 +1,267 @@
+/*!
+Embedded inventories of legal Hanyu Pinyin and LSHK Jyutping syllables, used to check that a
+`Syllable` is a real syllable and that its tone is possible.
+
+Pinyin syllables are spelled the way CC-CEDICT spells them, so ü is written `u:` after `l` and `n`
+and as `u` after `j`, `q`, `x` and `y`. Jyutping syllables are built from an initial and a final
+with a few co-occurrence restrictions.
+
+# Usage:
+```
+use cccedict::inventory::*;
+
+assert!(is_pinyin_syllable("hao"));
+assert!(is_pinyin_syllable("lu:e"));
+assert!(!is_pinyin_syllable("blorp"));
+
+assert!(is_jyutping_syllable("hou"));
+assert!(is_jyutping_syllable("gwok"));
+assert!(!is_jyutping_syllable("gwu"));
+assert_eq!(split_jyutping("gwok"), Some(("gw", "ok")));
+
+assert_eq!(split_pinyin("you"), Some(("", "iou".to_string())));
+assert_eq!(split_pinyin("lu:e"), Some(("l", "üe".to_string())));
+```
+*/
+
+/// Every Hanyu Pinyin syllable in CC-CEDICT spelling, sorted. Includes the interjections `m`,
+/// `n`, `ng`, `hm` and `hng`, and `r` for erhua.
+pub const PINYIN_SYLLABLES: &[&str] = &[
+    "a", "ai", "an", "ang", "ao", "ba", "bai", "ban", "bang", "bao", "bei", "ben", "beng", "bi",
+    "bian", "biao", "bie", "bin", "bing", "bo", "bu", "ca", "cai", "can", "cang", "cao", "ce",
+    "cei", "cen", "ceng", "cha", "chai", "chan", "chang", "chao", "che", "chen", "cheng", "chi",
+    "chong", "chou", "chu", "chua", "chuai", "chuan", "chuang", "chui", "chun", "chuo", "ci",
+    "cong", "cou", "cu", "cuan", "cui", "cun", "cuo", "da", "dai", "dan", "dang", "dao", "de",
+    "dei", "den", "deng", "di", "dia", "dian", "diao", "die", "ding", "diu", "dong", "dou", "du",
+    "duan", "dui", "dun", "duo", "e", "ei", "en", "eng", "er", "fa", "fan", "fang", "fei", "fen",
+    "feng", "fiao", "fo", "fou", "fu", "ga", "gai", "gan", "gang", "gao", "ge", "gei", "gen",
+    "geng", "gong", "gou", "gu", "gua", "guai", "guan", "guang", "gui", "gun", "guo", "ha", "hai",
+    "han", "hang", "hao", "he", "hei", "hen", "heng", "hm", "hng", "hong", "hou", "hu", "hua",
+    "huai", "huan", "huang", "hui", "hun", "huo", "ji", "jia", "jian", "jiang", "jiao", "jie",
+    "jin", "jing", "jiong", "jiu", "ju", "juan", "jue", "jun", "ka", "kai", "kan", "kang", "kao",
+    "ke", "kei", "ken", "keng", "kong", "kou", "ku", "kua", "kuai", "kuan", "kuang", "kui", "kun",
+    "kuo", "la", "lai", "lan", "lang", "lao", "le", "lei", "leng", "li", "lia", "lian", "liang",
+    "liao", "lie", "lin", "ling", "liu", "lo", "long", "lou", "lu", "lu:", "lu:e", "luan", "lun",
+    "luo", "m", "ma", "mai", "man", "mang", "mao", "me", "mei", "men", "meng", "mi", "mian",
+    "miao", "mie", "min", "ming", "miu", "mo", "mou", "mu", "n", "na", "nai", "nan", "nang", "nao",
+    "ne", "nei", "nen", "neng", "ng", "ni", "nian", "niang", "niao", "nie", "nin", "ning", "niu",
+    "nong", "nou", "nu", "nu:", "nu:e", "nuan", "nun", "nuo", "o", "ou", "pa", "pai", "pan",
+    "pang", "pao", "pei", "pen", "peng", "pi", "pian", "piao", "pie", "pin", "ping", "po", "pou",
+    "pu", "qi", "qia", "qian", "qiang", "qiao", "qie", "qin", "qing", "qiong", "qiu", "qu", "quan",
+    "que", "qun", "r", "ran", "rang", "rao", "re", "ren", "reng", "ri", "rong", "rou", "ru", "rua",
+    "ruan", "rui", "run", "ruo", "sa", "sai", "san", "sang", "sao", "se", "sen", "seng", "sha",
+    "shai", "shan", "shang", "shao", "she", "shei", "shen", "sheng", "shi", "shou", "shu", "shua",
+    "shuai", "shuan", "shuang", "shui", "shun", "shuo", "si", "song", "sou", "su", "suan", "sui",
+    "sun", "suo", "ta", "tai", "tan", "tang", "tao", "te", "tei", "teng", "ti", "tian", "tiao",
+    "tie", "ting", "tong", "tou", "tu", "tuan", "tui", "tun", "tuo", "wa", "wai", "wan", "wang",
+    "wei", "wen", "weng", "wo", "wu", "xi", "xia", "xian", "xiang", "xiao", "xie", "xin", "xing",
+    "xiong", "xiu", "xu", "xuan", "xue", "xun", "ya", "yan", "yang", "yao", "ye", "yi", "yin",
+    "ying", "yo", "yong", "you", "yu", "yuan", "yue", "yun", "za", "zai", "zan", "zang", "zao",
+    "ze", "zei", "zen", "zeng", "zha", "zhai", "zhan", "zhang", "zhao", "zhe", "zhei", "zhen",
+    "zheng", "zhi", "zhong", "zhou", "zhu", "zhua", "zhuai", "zhuan", "zhuang", "zhui", "zhun",
+    "zhuo", "zi", "zong", "zou", "zu", "zuan", "zui", "zun", "zuo",
+];
+
+/// Jyutping initials, longest first so that `gw` and `ng` are matched before `g` and `n`.
+pub const JYUTPING_INITIALS: &[&str] = &[
+    "gw", "kw", "ng", "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "w", "z", "c", "s",
+    "j",
+];
+
+/// Jyutping finals, including the syllabic nasals `m` and `ng`.
+pub const JYUTPING_FINALS: &[&str] = &[
+    "aa", "aai", "aau", "aam", "aan", "aang", "aap", "aat", "aak", "ai", "au", "am", "an", "ang",
+    "ap", "at", "ak", "e", "ei", "eu", "em", "en", "eng", "ep", "et", "ek", "i", "iu", "im", "in",
+    "ing", "ip", "it", "ik", "o", "oi", "ou", "on", "ong", "ot", "ok", "oe", "oeng", "oet", "oek",
+    "eoi", "eon", "eot", "u", "ui", "un", "ung", "ut", "uk", "yu", "yun", "yut", "m", "ng",
+];
+
+/// Whether `syllable` is a legal pinyin syllable, ignoring case. `ü` and `v` are accepted as
+/// alternative spellings of `u:`.
+pub fn is_pinyin_syllable(syllable: &str) -> bool {
+    let normalized = normalize_pinyin(syllable);
+    PINYIN_SYLLABLES.binary_search(&normalized.as_str()).is_ok()
+}
+
+/// Whether `syllable` is a legal jyutping syllable, ignoring case.
+pub fn is_jyutping_syllable(syllable: &str) -> bool {
+    split_jyutping(&syllable.to_lowercase()).is_some()
+}
+
+/// Splits a lowercase jyutping syllable into its initial, which may be empty, and its final.
+/// Returns `None` if the syllable is not legal.
+pub fn split_jyutping(syllable: &str) -> Option<(&str, &str)> {
+    let initial = JYUTPING_INITIALS
+        .iter()
+        .find(|initial| {
+            syllable.starts_with(*initial)
+                && is_jyutping_combination(initial, &syllable[initial.len()..])
+        })
+        .copied()
+        .unwrap_or("");
+
+    let final_ = &syllable[initial.len()..];
+    if is_jyutping_combination(initial, final_) {
+        Some((initial, final_))
+    } else {
+        None
+    }
+}
+
+fn is_jyutping_combination(initial: &str, final_: &str) -> bool {
+    if !JYUTPING_FINALS.contains(&final_) {
+        return false;
+    }
+
+    let starts_with_any = |prefixes: &[&str]| prefixes.iter().any(|p| final_.starts_with(p));
+
+    match initial {
+        // Syllabic nasals stand alone, apart from the interjections hm and hng
+        _ if final_ == "m" || final_ == "ng" => initial.is_empty() || initial == "h",
+        // High vowels are written with a j or w glide, except before -k and -ng as in uk1 屋
+        "" => !starts_with_any(&["i", "yu", "eo"]) && !["u", "ui", "un", "ut"].contains(&final_),
+        "gw" | "kw" => !starts_with_any(&["u", "yu", "oe", "eo"]),
+        "w" => !starts_with_any(&["yu", "oe", "eo"]),
+        _ => true,
+    }
+}
+
+/// Whether a jyutping final ends in an unreleased stop, which only occurs with the entering
+/// tones 1, 3 and 6 (or 7, 8 and 9).
+pub fn is_checked_final(final_: &str) -> bool {
+    final_.ends_with('p') || final_.ends_with('t') || final_.ends_with('k')
+}
+
+/// Pinyin initials, longest first so that `zh` is found before `z`.
+pub const PINYIN_INITIALS: &[&str] = &[
+    "zh", "ch", "sh", "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "j", "q", "x", "r",
+    "z", "c", "s",
+];
+
+/// Splits a pinyin syllable into its initial, which may be empty, and its final, ignoring case.
+/// Returns `None` if the syllable is not legal.
+///
+/// The final is spelled in full, the way it would be without spelling rules: `y` and `w` are
+/// dropped or turned back into `i` and `u`, ü is written `ü`, and the abbreviations `iu`, `ui`
+/// and `un` are expanded to `iou`, `uei` and `uen`. The buzzed vowel of `zhi` or `si` is an empty
+/// final, while the interjections and erhua are finals of their own: `m`, `n`, `ng`, `r` and
+/// `er`, so `hng` is `h` and `ng`.
+pub fn split_pinyin(syllable: &str) -> Option<(&'static str, String)> {
+    let syllable = normalize_pinyin(syllable);
+    if !is_pinyin_syllable(&syllable) {
+        return None;
+    }
+
+    match syllable.as_str() {
+        "m" | "n" | "ng" | "r" | "er" => return Some(("", syllable)),
+        "hm" | "hng" => return Some(("h", syllable[1..].to_string())),
+        _ => {}
+    }
+
+    let syllable = syllable.replace("u:", "ü");
+    let initial = PINYIN_INITIALS
+        .iter()
+        .copied()
+        .find(|initial| syllable.starts_with(initial))
+        .unwrap_or("");
+    let final_ = &syllable[initial.len()..];
+
+    let final_ = if let Some(rest) = final_.strip_prefix('y') {
+        match rest {
+            f if f.starts_with('u') => f.replacen('u', "ü", 1),
+            f if f.starts_with('i') => f.to_string(),
+            f => format!("i{}", f),
+        }
+    } else if let Some(rest) = final_.strip_prefix('w') {
+        match rest {
+            "u" => "u".to_string(),
+            f => format!("u{}", f),
+        }
+    } else {
+        match initial {
+            "j" | "q" | "x" if final_.starts_with('u') => final_.replacen('u', "ü", 1),
+            "zh" | "ch" | "sh" | "r" | "z" | "c" | "s" if final_ == "i" => String::new(),
+            _ => match final_ {
+                "iu" => "iou".to_string(),
+                "ui" => "uei".to_string(),
+                "un" => "uen".to_string(),
+                f => f.to_string(),
+            },
+        }
+    };
+
+    Some((initial, final_))
+}
+
+/// Lowercases `syllable` and respells `ü` and `v` as `u:`.
+pub(crate) fn normalize_pinyin(syllable: &str) -> String {
+    syllable.to_lowercase().replace(['ü', 'v'], "u:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinyin_syllables_are_sorted() {
+        let mut sorted = PINYIN_SYLLABLES.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted, PINYIN_SYLLABLES);
+    }
+
+    #[test]
+    fn test_pinyin_syllables() {
+        for syllable in ["ni", "Hao", "zhuang", "lu:", "lv", "nüe", "r", "hng"].iter() {
+            assert!(is_pinyin_syllable(syllable), "{}", syllable);
+        }
+
+        for syllable in ["blorp", "xianzai", "lu:n", "gi", "zhia", ""].iter() {
+            assert!(!is_pinyin_syllable(syllable), "{}", syllable);
+        }
+    }
+
+    #[test]
+    fn test_jyutping_syllables() {
+        for syllable in [
+            "nei", "hou", "maa", "jat", "ngo", "m", "ng", "hm", "jyut", "wu", "uk",
+        ]
+        .iter()
+        {
+            assert!(is_jyutping_syllable(syllable), "{}", syllable);
+        }
+
+        for syllable in ["blorp", "zhi", "hao", "u", "gwu", "bm", "kwoe", ""].iter() {
+            assert!(!is_jyutping_syllable(syllable), "{}", syllable);
+        }
+    }
+
+    #[test]
+    fn test_split_pinyin() {
+        let split = split_pinyin;
+
+        assert_eq!(split("Zhuang"), Some(("zh", "uang".to_string())));
+        assert_eq!(split("zhi"), Some(("zh", "".to_string())));
+        assert_eq!(split("ji"), Some(("j", "i".to_string())));
+        assert_eq!(split("yi"), Some(("", "i".to_string())));
+        assert_eq!(split("yuan"), Some(("", "üan".to_string())));
+        assert_eq!(split("wu"), Some(("", "u".to_string())));
+        assert_eq!(split("wei"), Some(("", "uei".to_string())));
+        assert_eq!(split("liu"), Some(("l", "iou".to_string())));
+        assert_eq!(split("dun"), Some(("d", "uen".to_string())));
+        assert_eq!(split("qun"), Some(("q", "ün".to_string())));
+        assert_eq!(split("nv"), Some(("n", "ü".to_string())));
+        assert_eq!(split("hng"), Some(("h", "ng".to_string())));
+        assert_eq!(split("er"), Some(("", "er".to_string())));
+        assert_eq!(split("blorp"), None);
+    }
+
+    #[test]
+    fn test_split_jyutping() {
+        assert_eq!(split_jyutping("ngo"), Some(("ng", "o")));
+        assert_eq!(split_jyutping("ng"), Some(("", "ng")));
+        assert_eq!(split_jyutping("aa"), Some(("", "aa")));
+        assert_eq!(split_jyutping("jyu"), Some(("j", "yu")));
+        assert_eq!(split_jyutping("gwaang"), Some(("gw", "aang")));
+    }
+}
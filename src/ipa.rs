@@ -0,0 +1,406 @@
+/*!
+Broad IPA transcriptions of pinyin and jyutping syllables, with tones written as Chao tone
+letters or tone numbers.
+
+# Usage:
+```
+use cccedict::ipa::*;
+use cccedict::syllable::*;
+
+let pinyin = [PinyinSyllable::new("ni", "3"), PinyinSyllable::new("hao", "3")];
+assert_eq!(pinyin.to_ipa(), "ni˨˩˦ xau̯˨˩˦");
+assert_eq!(pinyin.to_ipa_with(ToneNotation::Numbers), "ni²¹⁴ xau̯²¹⁴");
+
+let jyutping = [JyutpingSyllable::new("nei", "5"), JyutpingSyllable::new("hou", "2")];
+assert_eq!(jyutping.to_ipa(), "nei̯˨˧ hou̯˧˥");
+```
+
+Level tones are written with a single tone letter, e.g. `˥` for Mandarin tone 1, and the
+Mandarin neutral tone is left unmarked, as are syllables without a tone. Cantonese entering
+tones are written like the level tones they belong to. Letters and punctuation are written as they
+are, and syllables that aren't pinyin or jyutping are written in numbered form.
+*/
+
+use crate::inventory;
+use crate::syllable::{Jyutping, Pinyin, Romanization, Syllable, SyllableKind, SyllableRef};
+use crate::tone::{CantoneseTone, MandarinTone};
+
+/// How tones are written after each syllable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToneNotation {
+    /// Chao tone letters, e.g. `˨˩˦`
+    #[default]
+    ChaoLetters,
+    /// Superscript Chao tone numbers, e.g. `²¹⁴`
+    Numbers,
+}
+
+/// Renders pinyin or jyutping in IPA, depending on the syllables' romanization.
+pub trait Ipa {
+    fn to_ipa_with(&self, notation: ToneNotation) -> String;
+
+    fn to_ipa(&self) -> String {
+        self.to_ipa_with(ToneNotation::default())
+    }
+}
+
+impl<'a> Ipa for SyllableRef<'a, Pinyin> {
+    fn to_ipa_with(&self, notation: ToneNotation) -> String {
+        if self.kind != SyllableKind::Syllable {
+            return self.pronunciation.to_string();
+        }
+
+        let ipa = inventory::split_pinyin(self.pronunciation).and_then(|(initial, final_)| {
+            let (_, initial) = MANDARIN_INITIALS.iter().find(|(i, _)| *i == initial)?;
+            let final_ = match (initial, final_.as_str()) {
+                // The buzzed vowel of zhi and si follows the place of the initial
+                (_, "") if ["ʈʂ", "ʈʂʰ", "ʂ", "ʐ"].contains(initial) => "ʐ̩",
+                (_, "") => "z̩",
+                (_, final_) => MANDARIN_FINALS.iter().find(|(f, _)| *f == final_)?.1,
+            };
+            Some(format!("{}{}", initial, final_))
+        });
+
+        match ipa {
+            Some(ipa) => {
+                let contour = self.mandarin_tone().and_then(mandarin_contour);
+                format!("{}{}", ipa, render_contour(contour, notation))
+            }
+            None => self.to_string(),
+        }
+    }
+}
+
+impl<'a> Ipa for SyllableRef<'a, Jyutping> {
+    fn to_ipa_with(&self, notation: ToneNotation) -> String {
+        if self.kind != SyllableKind::Syllable {
+            return self.pronunciation.to_string();
+        }
+
+        let lowercase = self.pronunciation.to_lowercase();
+        let ipa = inventory::split_jyutping(&lowercase).and_then(|(initial, final_)| {
+            let (_, initial) = CANTONESE_INITIALS.iter().find(|(i, _)| *i == initial)?;
+            let (_, final_) = CANTONESE_FINALS.iter().find(|(f, _)| *f == final_)?;
+            Some(format!("{}{}", initial, final_))
+        });
+
+        match ipa {
+            Some(ipa) => {
+                let contour = self.cantonese_tone().map(cantonese_contour);
+                format!("{}{}", ipa, render_contour(contour, notation))
+            }
+            None => self.to_string(),
+        }
+    }
+}
+
+impl<R: Romanization> Ipa for Syllable<R>
+where
+    for<'a> SyllableRef<'a, R>: Ipa,
+{
+    fn to_ipa_with(&self, notation: ToneNotation) -> String {
+        self.as_ref().to_ipa_with(notation)
+    }
+}
+
+impl<'a, R: Romanization> Ipa for [SyllableRef<'a, R>]
+where
+    SyllableRef<'a, R>: Ipa,
+{
+    fn to_ipa_with(&self, notation: ToneNotation) -> String {
+        join(self.iter().map(|s| s.to_ipa_with(notation)))
+    }
+}
+
+impl<R: Romanization> Ipa for [Syllable<R>]
+where
+    for<'a> SyllableRef<'a, R>: Ipa,
+{
+    fn to_ipa_with(&self, notation: ToneNotation) -> String {
+        join(self.iter().map(|s| s.to_ipa_with(notation)))
+    }
+}
+
+fn join<I: Iterator<Item = String>>(syllables: I) -> String {
+    syllables.collect::<Vec<_>>().join(" ")
+}
+
+/// Chao tone numbers for a Mandarin tone, or `None` for the neutral tone.
+fn mandarin_contour(tone: MandarinTone) -> Option<&'static str> {
+    match tone {
+        MandarinTone::First => Some("55"),
+        MandarinTone::Second => Some("35"),
+        MandarinTone::Third => Some("214"),
+        MandarinTone::Fourth => Some("51"),
+        MandarinTone::Neutral => None,
+    }
+}
+
+fn cantonese_contour(tone: CantoneseTone) -> &'static str {
+    match tone.to_six_tone() {
+        CantoneseTone::First => "55",
+        CantoneseTone::Second => "35",
+        CantoneseTone::Third => "33",
+        CantoneseTone::Fourth => "21",
+        CantoneseTone::Fifth => "23",
+        _ => "22",
+    }
+}
+
+fn render_contour(contour: Option<&str>, notation: ToneNotation) -> String {
+    let contour = match contour {
+        Some(contour) => contour,
+        None => return String::new(),
+    };
+
+    match notation {
+        ToneNotation::ChaoLetters => {
+            let mut letters: Vec<char> = contour.chars().map(tone_letter).collect();
+            letters.dedup();
+            letters.into_iter().collect()
+        }
+        ToneNotation::Numbers => contour.chars().map(superscript).collect(),
+    }
+}
+
+fn tone_letter(digit: char) -> char {
+    match digit {
+        '5' => '˥',
+        '4' => '˦',
+        '3' => '˧',
+        '2' => '˨',
+        _ => '˩',
+    }
+}
+
+fn superscript(digit: char) -> char {
+    match digit {
+        '5' => '⁵',
+        '4' => '⁴',
+        '3' => '³',
+        '2' => '²',
+        _ => '¹',
+    }
+}
+
+const MANDARIN_INITIALS: [(&str, &str); 22] = [
+    ("", ""),
+    ("b", "p"),
+    ("p", "pʰ"),
+    ("m", "m"),
+    ("f", "f"),
+    ("d", "t"),
+    ("t", "tʰ"),
+    ("n", "n"),
+    ("l", "l"),
+    ("g", "k"),
+    ("k", "kʰ"),
+    ("h", "x"),
+    ("j", "tɕ"),
+    ("q", "tɕʰ"),
+    ("x", "ɕ"),
+    ("zh", "ʈʂ"),
+    ("ch", "ʈʂʰ"),
+    ("sh", "ʂ"),
+    ("r", "ʐ"),
+    ("z", "ts"),
+    ("c", "tsʰ"),
+    ("s", "s"),
+];
+
+/// Pinyin finals, as spelled by `inventory::split_pinyin`, in IPA.
+const MANDARIN_FINALS: [(&str, &str); 42] = [
+    ("a", "a"),
+    ("o", "o"),
+    ("e", "ɤ"),
+    ("ai", "ai̯"),
+    ("ei", "ei̯"),
+    ("ao", "au̯"),
+    ("ou", "ou̯"),
+    ("an", "an"),
+    ("en", "ən"),
+    ("ang", "aŋ"),
+    ("eng", "əŋ"),
+    ("ong", "ʊŋ"),
+    ("er", "aɚ̯"),
+    ("i", "i"),
+    ("ia", "ja"),
+    ("io", "jo"),
+    ("ie", "jɛ"),
+    ("iai", "jai̯"),
+    ("iao", "jau̯"),
+    ("iou", "jou̯"),
+    ("ian", "jɛn"),
+    ("in", "in"),
+    ("iang", "jaŋ"),
+    ("ing", "iŋ"),
+    ("iong", "jʊŋ"),
+    ("u", "u"),
+    ("ua", "wa"),
+    ("uo", "wo"),
+    ("uai", "wai̯"),
+    ("uei", "wei̯"),
+    ("uan", "wan"),
+    ("uen", "wən"),
+    ("uang", "waŋ"),
+    ("ueng", "wəŋ"),
+    ("ü", "y"),
+    ("üe", "ɥɛ"),
+    ("üan", "ɥɛn"),
+    ("ün", "yn"),
+    ("m", "m̩"),
+    ("n", "n̩"),
+    ("ng", "ŋ̍"),
+    ("r", "ɚ"),
+];
+
+const CANTONESE_INITIALS: [(&str, &str); 20] = [
+    ("", ""),
+    ("b", "p"),
+    ("p", "pʰ"),
+    ("m", "m"),
+    ("f", "f"),
+    ("d", "t"),
+    ("t", "tʰ"),
+    ("n", "n"),
+    ("l", "l"),
+    ("g", "k"),
+    ("k", "kʰ"),
+    ("ng", "ŋ"),
+    ("h", "h"),
+    ("gw", "kʷ"),
+    ("kw", "kʷʰ"),
+    ("w", "w"),
+    ("z", "ts"),
+    ("c", "tsʰ"),
+    ("s", "s"),
+    ("j", "j"),
+];
+
+const CANTONESE_FINALS: [(&str, &str); 59] = [
+    ("aa", "aː"),
+    ("aai", "aːi̯"),
+    ("aau", "aːu̯"),
+    ("aam", "aːm"),
+    ("aan", "aːn"),
+    ("aang", "aːŋ"),
+    ("aap", "aːp̚"),
+    ("aat", "aːt̚"),
+    ("aak", "aːk̚"),
+    ("ai", "ɐi̯"),
+    ("au", "ɐu̯"),
+    ("am", "ɐm"),
+    ("an", "ɐn"),
+    ("ang", "ɐŋ"),
+    ("ap", "ɐp̚"),
+    ("at", "ɐt̚"),
+    ("ak", "ɐk̚"),
+    ("e", "ɛː"),
+    ("ei", "ei̯"),
+    ("eu", "ɛːu̯"),
+    ("em", "ɛːm"),
+    ("en", "ɛːn"),
+    ("eng", "ɛːŋ"),
+    ("ep", "ɛːp̚"),
+    ("et", "ɛːt̚"),
+    ("ek", "ɛːk̚"),
+    ("i", "iː"),
+    ("iu", "iːu̯"),
+    ("im", "iːm"),
+    ("in", "iːn"),
+    ("ing", "eŋ"),
+    ("ip", "iːp̚"),
+    ("it", "iːt̚"),
+    ("ik", "ek̚"),
+    ("o", "ɔː"),
+    ("oi", "ɔːy̯"),
+    ("ou", "ou̯"),
+    ("on", "ɔːn"),
+    ("ong", "ɔːŋ"),
+    ("ot", "ɔːt̚"),
+    ("ok", "ɔːk̚"),
+    ("oe", "œː"),
+    ("oeng", "œːŋ"),
+    ("oet", "œːt̚"),
+    ("oek", "œːk̚"),
+    ("eoi", "ɵy̯"),
+    ("eon", "ɵn"),
+    ("eot", "ɵt̚"),
+    ("u", "uː"),
+    ("ui", "uːy̯"),
+    ("un", "uːn"),
+    ("ung", "oŋ"),
+    ("ut", "uːt̚"),
+    ("uk", "ok̚"),
+    ("yu", "yː"),
+    ("yun", "yːn"),
+    ("yut", "yːt̚"),
+    ("m", "m̩"),
+    ("ng", "ŋ̍"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syllable::{JyutpingSyllable, PinyinSyllable};
+
+    #[test]
+    fn test_mandarin_ipa() {
+        assert_eq!(PinyinSyllable::new("zhi", "1").to_ipa(), "ʈʂʐ̩˥");
+        assert_eq!(PinyinSyllable::new("si", "4").to_ipa(), "sz̩˥˩");
+        assert_eq!(PinyinSyllable::new("Xue", "2").to_ipa(), "ɕɥɛ˧˥");
+        assert_eq!(PinyinSyllable::new("lu:", "4").to_ipa(), "ly˥˩");
+        assert_eq!(PinyinSyllable::new("wen", "2").to_ipa(), "wən˧˥");
+        assert_eq!(PinyinSyllable::new("qiong", "2").to_ipa(), "tɕʰjʊŋ˧˥");
+        assert_eq!(PinyinSyllable::new("er", "4").to_ipa(), "aɚ̯˥˩");
+        assert_eq!(PinyinSyllable::new("hng", "5").to_ipa(), "xŋ̍");
+        assert_eq!(PinyinSyllable::new("ma", "").to_ipa(), "ma");
+    }
+
+    #[test]
+    fn test_cantonese_ipa() {
+        assert_eq!(JyutpingSyllable::new("gwong", "2").to_ipa(), "kʷɔːŋ˧˥");
+        assert_eq!(JyutpingSyllable::new("jyut", "6").to_ipa(), "jyːt̚˨");
+        assert_eq!(JyutpingSyllable::new("sik", "7").to_ipa(), "sek̚˥");
+        assert_eq!(JyutpingSyllable::new("heoi", "3").to_ipa(), "hɵy̯˧");
+        assert_eq!(JyutpingSyllable::new("ng", "4").to_ipa(), "ŋ̍˨˩");
+        assert_eq!(
+            JyutpingSyllable::new("loeng", "4").to_ipa_with(ToneNotation::Numbers),
+            "lœːŋ²¹"
+        );
+    }
+
+    #[test]
+    fn test_fallbacks() {
+        let syllables = [
+            PinyinSyllable::letter("A"),
+            PinyinSyllable::new("blorp", "1"),
+            PinyinSyllable::punctuation("·"),
+        ];
+        assert_eq!(syllables.to_ipa(), "A blorp1 ·");
+
+        let syllables = [
+            JyutpingSyllable::letter("A"),
+            JyutpingSyllable::new("blorp", "1"),
+        ];
+        assert_eq!(syllables.to_ipa(), "A blorp1");
+    }
+
+    #[test]
+    fn test_every_syllable_has_ipa() {
+        for pinyin in inventory::PINYIN_SYLLABLES {
+            let ipa = PinyinSyllable::new(pinyin, "1").to_ipa();
+            assert!(ipa.ends_with('˥'), "{}", pinyin);
+        }
+
+        for initial in inventory::JYUTPING_INITIALS.iter().chain([""].iter()) {
+            for final_ in inventory::JYUTPING_FINALS {
+                let jyutping = format!("{}{}", initial, final_);
+                if inventory::is_jyutping_syllable(&jyutping) {
+                    let ipa = JyutpingSyllable::new(&jyutping, "1").to_ipa();
+                    assert!(ipa.ends_with('˥'), "{}", jyutping);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,535 @@
+/*!
+Conversions from jyutping, as used in cantonese.org `{...}` readings, to other Cantonese
+romanizations: Yale, with tone numbers or with diacritics, and Sidney Lau. Yale can also be
+parsed back into jyutping.
+
+# Usage:
+```
+use cccedict::cedict_entry::CedictEntry;
+use cccedict::jyutping::*;
+use cccedict::syllable::Syllable;
+
+let entry = CedictEntry::new("廣東話 广东话 [Guang3 dong1 hua4] {gwong2 dung1 waa2} /Cantonese/").unwrap();
+let jyutping = entry.jyutping.unwrap();
+
+assert_eq!(jyutping.to_yale(), "gwóng dūng wá");
+assert_eq!(jyutping.to_yale_with(YaleTones::Numbered), "gwong2 dung1 wa2");
+assert_eq!(jyutping.to_sidney_lau(), "gwong2 dung1 wa2");
+
+assert_eq!(parse_yale("gwóng dūng wá").unwrap(), jyutping);
+assert_eq!(
+    parse_yale("yùh").unwrap(),
+    vec![Syllable::new("jyu", "4")]
+);
+```
+
+In Yale, the low tones 4, 5 and 6 are marked with an `h` after the vowels of the syllable, as in
+`yùh` or `lèuhng`, and tones 1, 2, 4 and 5 also take a diacritic. Tone 1 is written with a
+macron; Yale's separate high falling tone, written with a grave accent and no `h`, is read back as
+tone 1 too. Both Yale and Sidney Lau write a final `aa` with nothing after it as `a`, and Sidney
+Lau writes the entering tones of syllables ending in -p, -t and -k as 7, 8 and 9. Syllables
+without a tone are written without one in every scheme.
+*/
+
+use crate::errors::YaleInputError;
+use crate::inventory;
+use crate::syllable::{Jyutping, Syllable, SyllableKind, SyllableRef};
+use crate::tone::CantoneseTone;
+
+/// How Yale tones are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum YaleTones {
+    /// Tone numbers 1 to 6 after each syllable, e.g. `yu4`
+    Numbered,
+    /// Diacritics and the low tone `h`, e.g. `yùh`
+    #[default]
+    Diacritics,
+}
+
+/// Renders jyutping in Yale romanization.
+///
+/// Letters and punctuation are written as they are, and syllables that aren't jyutping are
+/// written unchanged.
+pub trait ToYale {
+    fn to_yale_with(&self, tones: YaleTones) -> String;
+
+    fn to_yale(&self) -> String {
+        self.to_yale_with(YaleTones::default())
+    }
+}
+
+/// Renders jyutping in Sidney Lau romanization.
+///
+/// Letters and punctuation are written as they are, and syllables that aren't jyutping are
+/// written unchanged.
+pub trait ToSidneyLau {
+    fn to_sidney_lau(&self) -> String;
+}
+
+impl<'a> ToYale for SyllableRef<'a, Jyutping> {
+    fn to_yale_with(&self, tones: YaleTones) -> String {
+        let (initial, final_) = match split(self) {
+            Some(parts) => parts,
+            None => return fallback(self),
+        };
+        let tone = self.cantonese_tone().map(CantoneseTone::to_six_tone);
+
+        let (initial, final_) = yale_spelling(&initial, &final_);
+        match (tones, tone) {
+            (_, None) => format!("{}{}", initial, final_),
+            (YaleTones::Numbered, Some(tone)) => format!("{}{}{}", initial, final_, tone),
+            (YaleTones::Diacritics, Some(tone)) => {
+                format!("{}{}", initial, mark_yale_final(&final_, tone))
+            }
+        }
+    }
+}
+
+impl ToYale for Syllable<Jyutping> {
+    fn to_yale_with(&self, tones: YaleTones) -> String {
+        self.as_ref().to_yale_with(tones)
+    }
+}
+
+impl<'a> ToYale for [SyllableRef<'a, Jyutping>] {
+    fn to_yale_with(&self, tones: YaleTones) -> String {
+        join(self.iter().map(|syllable| syllable.to_yale_with(tones)))
+    }
+}
+
+impl ToYale for [Syllable<Jyutping>] {
+    fn to_yale_with(&self, tones: YaleTones) -> String {
+        join(self.iter().map(|syllable| syllable.to_yale_with(tones)))
+    }
+}
+
+impl<'a> ToSidneyLau for SyllableRef<'a, Jyutping> {
+    fn to_sidney_lau(&self) -> String {
+        let (initial, final_) = match split(self) {
+            Some(parts) => parts,
+            None => return fallback(self),
+        };
+
+        let tone = match self.cantonese_tone().map(CantoneseTone::to_six_tone) {
+            Some(tone) if inventory::is_checked_final(&final_) => match tone.number() {
+                1 => "7".to_string(),
+                3 => "8".to_string(),
+                6 => "9".to_string(),
+                // Changed tones, as in colloquial aap2 鴨, are written as they are
+                _ => tone.to_string(),
+            },
+            Some(tone) => tone.to_string(),
+            None => String::new(),
+        };
+
+        let (initial, final_) = sidney_lau_spelling(&initial, &final_);
+        format!("{}{}{}", initial, final_, tone)
+    }
+}
+
+impl ToSidneyLau for Syllable<Jyutping> {
+    fn to_sidney_lau(&self) -> String {
+        self.as_ref().to_sidney_lau()
+    }
+}
+
+impl<'a> ToSidneyLau for [SyllableRef<'a, Jyutping>] {
+    fn to_sidney_lau(&self) -> String {
+        join(self.iter().map(ToSidneyLau::to_sidney_lau))
+    }
+}
+
+impl ToSidneyLau for [Syllable<Jyutping>] {
+    fn to_sidney_lau(&self) -> String {
+        join(self.iter().map(ToSidneyLau::to_sidney_lau))
+    }
+}
+
+fn join<I: Iterator<Item = String>>(syllables: I) -> String {
+    syllables.collect::<Vec<_>>().join(" ")
+}
+
+fn fallback(syllable: &SyllableRef<Jyutping>) -> String {
+    match syllable.kind {
+        SyllableKind::Syllable => syllable.to_string(),
+        _ => syllable.pronunciation.to_string(),
+    }
+}
+
+/// Splits a jyutping syllable into its lowercase initial and final.
+fn split(syllable: &SyllableRef<Jyutping>) -> Option<(String, String)> {
+    if syllable.kind != SyllableKind::Syllable {
+        return None;
+    }
+
+    let lowercase = syllable.pronunciation.to_lowercase();
+    let (initial, final_) = inventory::split_jyutping(&lowercase)?;
+    Some((initial.to_string(), final_.to_string()))
+}
+
+fn yale_spelling(initial: &str, final_: &str) -> (String, String) {
+    let initial = match initial {
+        "z" => "j",
+        "c" => "ch",
+        "j" => "y",
+        initial => initial,
+    };
+    // jyu is written yu, with the y doing double duty
+    let final_ = match final_.strip_prefix('y') {
+        Some(rest) if initial == "y" => rest,
+        _ => final_,
+    };
+
+    // Yale only doubles the a when something follows it
+    let final_ = if final_ == "aa" {
+        "a".to_string()
+    } else if final_.starts_with("oe") || final_.starts_with("eo") {
+        format!("eu{}", &final_[2..])
+    } else {
+        final_.to_string()
+    };
+
+    (initial.to_string(), final_)
+}
+
+/// Adds the tone diacritic to the first vowel of a Yale final, and the low tone `h` after its
+/// vowels. Syllabic nasals take both on the nasal itself.
+fn mark_yale_final(final_: &str, tone: CantoneseTone) -> String {
+    let mark = match tone.number() {
+        1 => Some(MACRON),
+        2 | 5 => Some(ACUTE),
+        4 => Some(GRAVE),
+        _ => None,
+    };
+    let low = tone.number() >= 4;
+
+    let chars: Vec<char> = final_.chars().collect();
+    let (mark_idx, h_idx) = match chars.iter().position(|c| is_vowel(*c)) {
+        Some(first) => {
+            let vowels = chars[first..].iter().take_while(|c| is_vowel(**c)).count();
+            (first, first + vowels)
+        }
+        None => (0, chars.len()),
+    };
+
+    let mut output = String::new();
+    for (idx, c) in chars.iter().enumerate() {
+        if idx == h_idx && low {
+            output.push('h');
+        }
+        match mark.filter(|_| idx == mark_idx) {
+            Some(mark) => match compose(*c, mark) {
+                Some(composed) => output.push(composed),
+                None => {
+                    output.push(*c);
+                    output.push(mark);
+                }
+            },
+            None => output.push(*c),
+        }
+    }
+    if h_idx == chars.len() && low {
+        output.push('h');
+    }
+
+    output
+}
+
+fn sidney_lau_spelling(initial: &str, final_: &str) -> (String, String) {
+    let initial = match initial {
+        "z" => "j",
+        "c" => "ch",
+        "j" => "y",
+        initial => initial,
+    };
+
+    let final_ = match final_ {
+        "aa" => "a",
+        "o" => "oh",
+        "ou" => "o",
+        "u" => "oo",
+        "ui" => "ooi",
+        "un" => "oon",
+        "ut" => "oot",
+        "oe" => "euh",
+        "oeng" => "eung",
+        "oek" => "euk",
+        "eoi" => "ui",
+        "eon" => "un",
+        "eot" => "ut",
+        "yu" => "ue",
+        "yun" => "uen",
+        "yut" => "uet",
+        final_ => final_,
+    };
+
+    (initial.to_string(), final_.to_string())
+}
+
+/// Parses Yale romanization, with diacritics or tone numbers, into jyutping `Syllable`s.
+///
+/// Syllables must be separated by whitespace, apostrophes or hyphens. Yale's `eu` stands for
+/// both jyutping `eo` and `oe`, and is read as `eo` before `i`, `n` and `t`, where only `eo`
+/// occurs, and as `oe` elsewhere.
+pub fn parse_yale(input: &str) -> Result<Vec<Syllable<Jyutping>>, YaleInputError> {
+    let mut syllables = vec![];
+    let mut start = None;
+
+    for (offset, c) in input.char_indices().chain([(input.len(), ' ')]) {
+        if c.is_whitespace() || c == '\'' || c == '-' {
+            if let Some(start) = start.take() {
+                let syllable = parse_yale_syllable(&input[start..offset])
+                    .ok_or_else(|| YaleInputError::new(input, start))?;
+                syllables.push(syllable);
+            }
+        } else if start.is_none() {
+            start = Some(offset);
+        }
+    }
+
+    Ok(syllables)
+}
+
+fn parse_yale_syllable(input: &str) -> Option<Syllable<Jyutping>> {
+    let mut letters = vec![];
+    let mut mark = None;
+    let mut number = None;
+
+    for c in input.chars() {
+        match c {
+            MACRON | ACUTE | GRAVE if mark.is_none() && !letters.is_empty() => mark = Some(c),
+            '1'..='9' if number.is_none() && !letters.is_empty() => number = Some(c as u8 - b'0'),
+            c if number.is_none() => {
+                let (letter, letter_mark) = decompose(c)?;
+                if letter_mark.is_some() {
+                    if mark.is_some() {
+                        return None;
+                    }
+                    mark = letter_mark;
+                }
+                letters.push(letter);
+            }
+            _ => return None,
+        }
+    }
+
+    // A low tone h follows the vowels, or a syllabic nasal as in ǹgh
+    let low_h = (1..letters.len()).find(|idx| {
+        let before: String = letters[..*idx].iter().collect();
+        letters[*idx] == 'h' && (before.chars().any(is_vowel) || before == "m" || before == "ng")
+    });
+    if let Some(idx) = low_h {
+        letters.remove(idx);
+    }
+
+    let tone = match (number, mark, low_h.is_some()) {
+        (Some(number), None, false) => CantoneseTone::from_number(number)?.to_six_tone(),
+        (Some(_), _, _) => return None,
+        (None, Some(MACRON), false) | (None, Some(GRAVE), false) => CantoneseTone::First,
+        (None, Some(ACUTE), false) => CantoneseTone::Second,
+        (None, None, false) => CantoneseTone::Third,
+        (None, Some(GRAVE), true) => CantoneseTone::Fourth,
+        (None, Some(ACUTE), true) => CantoneseTone::Fifth,
+        (None, None, true) => CantoneseTone::Sixth,
+        (None, Some(_), _) => return None,
+    };
+
+    let yale: String = letters.iter().collect();
+    let jyutping = jyutping_spelling(&yale);
+    if !inventory::is_jyutping_syllable(&jyutping) {
+        return None;
+    }
+
+    Some(Syllable::new(&jyutping, &tone.to_string()))
+}
+
+/// Respells a lowercase Yale syllable, without tones, in jyutping.
+fn jyutping_spelling(yale: &str) -> String {
+    let respelled = if let Some(rest) = yale.strip_prefix("ch") {
+        format!("c{}", rest)
+    } else if let Some(rest) = yale.strip_prefix('j') {
+        format!("z{}", rest)
+    } else if let Some(rest) = yale.strip_prefix('y') {
+        // Yale yu is jyutping jyu, but yung and yuk keep their u, as in jung6 用 and juk6 肉
+        let jyu = format!("jy{}", rest);
+        if rest.starts_with('u') && inventory::is_jyutping_syllable(&jyu) {
+            jyu
+        } else {
+            format!("j{}", rest)
+        }
+    } else {
+        yale.to_string()
+    };
+
+    // A lone a is always long, as jyutping has no final a
+    let respelled = match respelled.strip_suffix('a') {
+        Some(rest) if !rest.ends_with('a') => format!("{}aa", rest),
+        _ => respelled,
+    };
+
+    match respelled.find("eu") {
+        Some(idx) => {
+            let after = &respelled[idx + 2..];
+            let vowel = match after {
+                "i" | "n" | "t" => "eo",
+                _ => "oe",
+            };
+            format!("{}{}{}", &respelled[..idx], vowel, after)
+        }
+        None => respelled,
+    }
+}
+
+const MACRON: char = '\u{304}';
+const ACUTE: char = '\u{301}';
+const GRAVE: char = '\u{300}';
+
+fn is_vowel(c: char) -> bool {
+    "aeiou".contains(c)
+}
+
+/// Precomposed letters with Yale tone marks.
+const COMPOSED: [(char, char, char); 18] = [
+    ('a', MACRON, 'ā'),
+    ('a', ACUTE, 'á'),
+    ('a', GRAVE, 'à'),
+    ('e', MACRON, 'ē'),
+    ('e', ACUTE, 'é'),
+    ('e', GRAVE, 'è'),
+    ('i', MACRON, 'ī'),
+    ('i', ACUTE, 'í'),
+    ('i', GRAVE, 'ì'),
+    ('o', MACRON, 'ō'),
+    ('o', ACUTE, 'ó'),
+    ('o', GRAVE, 'ò'),
+    ('u', MACRON, 'ū'),
+    ('u', ACUTE, 'ú'),
+    ('u', GRAVE, 'ù'),
+    ('m', ACUTE, 'ḿ'),
+    ('n', ACUTE, 'ń'),
+    ('n', GRAVE, 'ǹ'),
+];
+
+fn compose(letter: char, mark: char) -> Option<char> {
+    COMPOSED
+        .iter()
+        .find(|(l, m, _)| *l == letter && *m == mark)
+        .map(|(_, _, composed)| *composed)
+}
+
+/// Splits a character into a lowercase ASCII letter and the Yale tone mark on it, if any.
+fn decompose(c: char) -> Option<(char, Option<char>)> {
+    if c.is_ascii_alphabetic() {
+        return Some((c.to_ascii_lowercase(), None));
+    }
+
+    let lowercase = c.to_lowercase().next()?;
+    COMPOSED
+        .iter()
+        .find(|(_, _, composed)| *composed == lowercase)
+        .map(|(letter, mark, _)| (*letter, Some(*mark)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jyutping(syllables: &str) -> Vec<Syllable<Jyutping>> {
+        syllables
+            .split(' ')
+            .map(|s| {
+                let (pronunciation, tone) = s.split_at(s.len() - 1);
+                Syllable::new(pronunciation, tone)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_to_yale() {
+        assert_eq!(jyutping("nei5 hou2").to_yale(), "néih hóu");
+        assert_eq!(jyutping("jyu4 jyut6").to_yale(), "yùh yuht");
+        assert_eq!(jyutping("loeng4 heoi3").to_yale(), "lèuhng heui");
+        assert_eq!(jyutping("zyu1 cin4 sap6").to_yale(), "jyū chìhn sahp");
+        assert_eq!(jyutping("ng4 m4 hm6").to_yale(), "ǹgh m\u{300}h hmh");
+        assert_eq!(jyutping("baak3 sik1").to_yale(), "baak sīk");
+        assert_eq!(jyutping("maa1 waa2 aa3").to_yale(), "mā wá a");
+        assert_eq!(Syllable::new("sik", "7").to_yale(), "sīk");
+        assert_eq!(Syllable::new("sik", "").to_yale(), "sik");
+    }
+
+    #[test]
+    fn test_to_yale_numbered() {
+        assert_eq!(
+            jyutping("jyu4 jyut6 loeng4 zoek3").to_yale_with(YaleTones::Numbered),
+            "yu4 yut6 leung4 jeuk3"
+        );
+    }
+
+    #[test]
+    fn test_to_sidney_lau() {
+        assert_eq!(jyutping("nei5 hou2").to_sidney_lau(), "nei5 ho2");
+        assert_eq!(jyutping("maa1 ngo5 fu1").to_sidney_lau(), "ma1 ngoh5 foo1");
+        assert_eq!(
+            jyutping("jyu4 jyut6 zyu1").to_sidney_lau(),
+            "yue4 yuet9 jue1"
+        );
+        assert_eq!(
+            jyutping("heoi3 seon3 hoe1").to_sidney_lau(),
+            "hui3 sun3 heuh1"
+        );
+        assert_eq!(
+            jyutping("sik1 baak3 sap6").to_sidney_lau(),
+            "sik7 baak8 sap9"
+        );
+        assert_eq!(jyutping("aap2 sik2").to_sidney_lau(), "aap2 sik2");
+    }
+
+    #[test]
+    fn test_fallbacks() {
+        let syllables = [
+            Syllable::letter("A"),
+            Syllable::new("zhi", "1"),
+            Syllable::punctuation("，"),
+        ];
+        assert_eq!(syllables.to_yale(), "A zhi1 ，");
+        assert_eq!(syllables.to_sidney_lau(), "A zhi1 ，");
+    }
+
+    #[test]
+    fn test_parse_yale() {
+        assert_eq!(parse_yale("Néih hóu").unwrap(), jyutping("nei5 hou2"));
+        assert_eq!(
+            parse_yale("lèuhng-heui jeuk").unwrap(),
+            jyutping("loeng4 heoi3 zoek3")
+        );
+        assert_eq!(parse_yale("ǹgh m\u{300}h").unwrap(), jyutping("ng4 m4"));
+        assert_eq!(parse_yale("yu4 yut6").unwrap(), jyutping("jyu4 jyut6"));
+        assert_eq!(parse_yale("yuhng yuhk").unwrap(), jyutping("jung6 juk6"));
+        assert_eq!(parse_yale("sik7").unwrap(), jyutping("sik1"));
+        assert_eq!(parse_yale("hòhng").unwrap(), jyutping("hong4"));
+        assert_eq!(parse_yale("sàam").unwrap(), jyutping("saam1"));
+        assert_eq!(parse_yale("mā a").unwrap(), jyutping("maa1 aa3"));
+    }
+
+    #[test]
+    fn test_parse_yale_errors() {
+        assert_eq!(
+            parse_yale("néih xyz"),
+            Err(YaleInputError::new("néih xyz", 6))
+        );
+        assert!(parse_yale("néih5").is_err());
+        assert!(parse_yale("hāh").is_err());
+    }
+
+    #[test]
+    fn test_yale_round_trip() {
+        let syllables = jyutping("gwong2 zau1 jan4 soeng5 hoi2 coek3 jyut6 ng5 jung6 juk6");
+
+        for tones in [YaleTones::Diacritics, YaleTones::Numbered] {
+            assert_eq!(
+                parse_yale(&syllables.to_yale_with(tones)).unwrap(),
+                syllables
+            );
+        }
+    }
+}
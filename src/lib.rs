@@ -7,5 +7,21 @@ for [jyutping](https://en.wikipedia.org/wiki/Jyutping) pronunciations.
 
 pub mod cedict;
 pub mod cedict_entry;
+pub mod cedict_metadata;
+pub mod cedict_reader;
+pub mod english_index;
 pub mod errors;
+pub mod fuzzy;
+pub mod index;
+pub mod inventory;
+pub mod ipa;
+pub mod jyutping;
+pub mod phonology;
+pub mod pinyin;
+pub mod prefix;
+pub mod reading_index;
+pub mod romanization;
+pub mod sandhi;
 pub mod syllable;
+pub mod tone;
+pub mod zhuyin;
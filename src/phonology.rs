@@ -0,0 +1,626 @@
+/*!
+Structured decompositions of syllables: pinyin into initial, medial and final, and jyutping into
+onset, nucleus and coda, each with its tone.
+
+# Usage:
+```
+use cccedict::phonology::*;
+use cccedict::syllable::*;
+use cccedict::tone::*;
+
+let parts = PinyinSyllable::new("xiang", "3").pinyin_parts().unwrap();
+assert_eq!(parts.initial, Some(PinyinInitial::X));
+assert_eq!(parts.medial, Some(PinyinMedial::I));
+assert_eq!(parts.final_, PinyinFinal::Ang);
+assert_eq!(parts.tone, Some(MandarinTone::Third));
+
+let parts = JyutpingSyllable::new("gwok", "3").jyutping_parts().unwrap();
+assert_eq!(parts.onset, Some(JyutpingOnset::Gw));
+assert_eq!(parts.nucleus, JyutpingNucleus::O);
+assert_eq!(parts.coda, Some(JyutpingCoda::K));
+assert_eq!(parts.tone, Some(CantoneseTone::Third));
+
+// Syllables rhyme when their finals match
+let rhymes = |a: &PinyinSyllable, b: &PinyinSyllable| {
+    a.pinyin_parts().map(|p| p.final_) == b.pinyin_parts().map(|p| p.final_)
+};
+assert!(rhymes(&Syllable::new("guang", "1"), &Syllable::new("chang", "2")));
+```
+
+Pinyin is decomposed with its spelling rules undone, the way `inventory::split_pinyin` spells
+finals: `you` has the medial `i` and the final `ou`, and `yu` has no medial and the final `ü`,
+which the enums spell `V`. The high vowels `i`, `u` and `ü` are finals of their own when nothing
+follows them, as are `in`, `ing` and `ün`. Jyutping syllabic nasals like `ng` 五 are nuclei.
+*/
+
+use crate::inventory;
+use crate::syllable::{Jyutping, Pinyin, SyllableKind, SyllableRef};
+use crate::tone::{CantoneseTone, MandarinTone};
+use std::fmt;
+
+/// A pinyin syllable split into its parts. Syllables without an initial, like `an`, have no
+/// initial, and most have no medial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PinyinParts {
+    pub initial: Option<PinyinInitial>,
+    pub medial: Option<PinyinMedial>,
+    pub final_: PinyinFinal,
+    pub tone: Option<MandarinTone>,
+}
+
+impl PinyinParts {
+    /// Decomposes a pinyin syllable, or returns `None` for letters, punctuation and syllables that
+    /// aren't pinyin.
+    pub fn new(syllable: SyllableRef<Pinyin>) -> Option<Self> {
+        if syllable.kind != SyllableKind::Syllable {
+            return None;
+        }
+
+        let (initial, final_) = inventory::split_pinyin(syllable.pronunciation)?;
+        let initial = match initial {
+            "" => None,
+            initial => Some(find(&PinyinInitial::ALL, initial, PinyinInitial::as_str)?),
+        };
+
+        let whole_final = find(&PinyinFinal::ALL, &final_, PinyinFinal::as_str);
+        let (medial, final_) = match whole_final {
+            Some(final_) => (None, final_),
+            None => {
+                let medial = PinyinMedial::ALL
+                    .iter()
+                    .copied()
+                    .find(|medial| final_.starts_with(medial.as_str()))?;
+                let rest = match &final_[medial.as_str().len()..] {
+                    // The e of ie and üe is ê
+                    "e" => PinyinFinal::EHat,
+                    rest => find(&PinyinFinal::ALL, rest, PinyinFinal::as_str)?,
+                };
+                (Some(medial), rest)
+            }
+        };
+
+        Some(PinyinParts {
+            initial,
+            medial,
+            final_,
+            tone: syllable.mandarin_tone(),
+        })
+    }
+}
+
+/// A jyutping syllable split into its parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JyutpingParts {
+    pub onset: Option<JyutpingOnset>,
+    pub nucleus: JyutpingNucleus,
+    pub coda: Option<JyutpingCoda>,
+    pub tone: Option<CantoneseTone>,
+}
+
+impl JyutpingParts {
+    /// Decomposes a jyutping syllable, or returns `None` for letters, punctuation and syllables
+    /// that aren't jyutping.
+    pub fn new(syllable: SyllableRef<Jyutping>) -> Option<Self> {
+        if syllable.kind != SyllableKind::Syllable {
+            return None;
+        }
+
+        let lowercase = syllable.pronunciation.to_lowercase();
+        let (onset, final_) = inventory::split_jyutping(&lowercase)?;
+        let onset = match onset {
+            "" => None,
+            onset => Some(find(&JyutpingOnset::ALL, onset, JyutpingOnset::as_str)?),
+        };
+
+        let (nucleus, coda) = match final_ {
+            "m" => (JyutpingNucleus::M, ""),
+            "ng" => (JyutpingNucleus::Ng, ""),
+            final_ => {
+                let nucleus = JyutpingNucleus::ALL
+                    .iter()
+                    .copied()
+                    .find(|nucleus| final_.starts_with(nucleus.as_str()))?;
+                (nucleus, &final_[nucleus.as_str().len()..])
+            }
+        };
+        let coda = match coda {
+            "" => None,
+            coda => Some(find(&JyutpingCoda::ALL, coda, JyutpingCoda::as_str)?),
+        };
+
+        Some(JyutpingParts {
+            onset,
+            nucleus,
+            coda,
+            tone: syllable.cantonese_tone(),
+        })
+    }
+}
+
+fn find<T: Copy>(all: &[T], spelling: &str, as_str: fn(T) -> &'static str) -> Option<T> {
+    all.iter().copied().find(|part| as_str(*part) == spelling)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PinyinInitial {
+    B,
+    P,
+    M,
+    F,
+    D,
+    T,
+    N,
+    L,
+    G,
+    K,
+    H,
+    J,
+    Q,
+    X,
+    Zh,
+    Ch,
+    Sh,
+    R,
+    Z,
+    C,
+    S,
+}
+
+impl PinyinInitial {
+    pub const ALL: [PinyinInitial; 21] = [
+        PinyinInitial::B,
+        PinyinInitial::P,
+        PinyinInitial::M,
+        PinyinInitial::F,
+        PinyinInitial::D,
+        PinyinInitial::T,
+        PinyinInitial::N,
+        PinyinInitial::L,
+        PinyinInitial::G,
+        PinyinInitial::K,
+        PinyinInitial::H,
+        PinyinInitial::J,
+        PinyinInitial::Q,
+        PinyinInitial::X,
+        PinyinInitial::Zh,
+        PinyinInitial::Ch,
+        PinyinInitial::Sh,
+        PinyinInitial::R,
+        PinyinInitial::Z,
+        PinyinInitial::C,
+        PinyinInitial::S,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PinyinInitial::B => "b",
+            PinyinInitial::P => "p",
+            PinyinInitial::M => "m",
+            PinyinInitial::F => "f",
+            PinyinInitial::D => "d",
+            PinyinInitial::T => "t",
+            PinyinInitial::N => "n",
+            PinyinInitial::L => "l",
+            PinyinInitial::G => "g",
+            PinyinInitial::K => "k",
+            PinyinInitial::H => "h",
+            PinyinInitial::J => "j",
+            PinyinInitial::Q => "q",
+            PinyinInitial::X => "x",
+            PinyinInitial::Zh => "zh",
+            PinyinInitial::Ch => "ch",
+            PinyinInitial::Sh => "sh",
+            PinyinInitial::R => "r",
+            PinyinInitial::Z => "z",
+            PinyinInitial::C => "c",
+            PinyinInitial::S => "s",
+        }
+    }
+}
+
+/// The glide between a pinyin initial and its final. `V` is ü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PinyinMedial {
+    I,
+    U,
+    V,
+}
+
+impl PinyinMedial {
+    pub const ALL: [PinyinMedial; 3] = [PinyinMedial::I, PinyinMedial::U, PinyinMedial::V];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PinyinMedial::I => "i",
+            PinyinMedial::U => "u",
+            PinyinMedial::V => "ü",
+        }
+    }
+}
+
+/// The rhyme of a pinyin syllable, after any medial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PinyinFinal {
+    A,
+    O,
+    E,
+    /// ê, the e of `ie` and `üe`
+    EHat,
+    Ai,
+    Ei,
+    Ao,
+    Ou,
+    An,
+    En,
+    Ang,
+    Eng,
+    Ong,
+    Er,
+    I,
+    U,
+    /// ü
+    V,
+    In,
+    Ing,
+    /// ün
+    Vn,
+    /// The buzzed vowel of `zhi`, `chi`, `shi`, `ri`, `zi`, `ci` and `si`, spelled `i`
+    Apical,
+    /// The syllabic nasal of the interjections `m` and `hm`
+    M,
+    /// The syllabic nasal of the interjection `n`
+    N,
+    /// The syllabic nasal of the interjections `ng` and `hng`
+    Ng,
+    /// Erhua `r`
+    R,
+}
+
+impl PinyinFinal {
+    pub const ALL: [PinyinFinal; 25] = [
+        PinyinFinal::A,
+        PinyinFinal::O,
+        PinyinFinal::E,
+        PinyinFinal::EHat,
+        PinyinFinal::Ai,
+        PinyinFinal::Ei,
+        PinyinFinal::Ao,
+        PinyinFinal::Ou,
+        PinyinFinal::An,
+        PinyinFinal::En,
+        PinyinFinal::Ang,
+        PinyinFinal::Eng,
+        PinyinFinal::Ong,
+        PinyinFinal::Er,
+        PinyinFinal::I,
+        PinyinFinal::U,
+        PinyinFinal::V,
+        PinyinFinal::In,
+        PinyinFinal::Ing,
+        PinyinFinal::Vn,
+        PinyinFinal::Apical,
+        PinyinFinal::M,
+        PinyinFinal::N,
+        PinyinFinal::Ng,
+        PinyinFinal::R,
+    ];
+
+    /// The final as `inventory::split_pinyin` spells it. The apical vowel is the empty string.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PinyinFinal::A => "a",
+            PinyinFinal::O => "o",
+            PinyinFinal::E => "e",
+            PinyinFinal::EHat => "ê",
+            PinyinFinal::Ai => "ai",
+            PinyinFinal::Ei => "ei",
+            PinyinFinal::Ao => "ao",
+            PinyinFinal::Ou => "ou",
+            PinyinFinal::An => "an",
+            PinyinFinal::En => "en",
+            PinyinFinal::Ang => "ang",
+            PinyinFinal::Eng => "eng",
+            PinyinFinal::Ong => "ong",
+            PinyinFinal::Er => "er",
+            PinyinFinal::I => "i",
+            PinyinFinal::U => "u",
+            PinyinFinal::V => "ü",
+            PinyinFinal::In => "in",
+            PinyinFinal::Ing => "ing",
+            PinyinFinal::Vn => "ün",
+            PinyinFinal::Apical => "",
+            PinyinFinal::M => "m",
+            PinyinFinal::N => "n",
+            PinyinFinal::Ng => "ng",
+            PinyinFinal::R => "r",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum JyutpingOnset {
+    B,
+    P,
+    M,
+    F,
+    D,
+    T,
+    N,
+    L,
+    G,
+    K,
+    Ng,
+    H,
+    Gw,
+    Kw,
+    W,
+    Z,
+    C,
+    S,
+    J,
+}
+
+impl JyutpingOnset {
+    pub const ALL: [JyutpingOnset; 19] = [
+        JyutpingOnset::B,
+        JyutpingOnset::P,
+        JyutpingOnset::M,
+        JyutpingOnset::F,
+        JyutpingOnset::D,
+        JyutpingOnset::T,
+        JyutpingOnset::N,
+        JyutpingOnset::L,
+        JyutpingOnset::G,
+        JyutpingOnset::K,
+        JyutpingOnset::Ng,
+        JyutpingOnset::H,
+        JyutpingOnset::Gw,
+        JyutpingOnset::Kw,
+        JyutpingOnset::W,
+        JyutpingOnset::Z,
+        JyutpingOnset::C,
+        JyutpingOnset::S,
+        JyutpingOnset::J,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JyutpingOnset::B => "b",
+            JyutpingOnset::P => "p",
+            JyutpingOnset::M => "m",
+            JyutpingOnset::F => "f",
+            JyutpingOnset::D => "d",
+            JyutpingOnset::T => "t",
+            JyutpingOnset::N => "n",
+            JyutpingOnset::L => "l",
+            JyutpingOnset::G => "g",
+            JyutpingOnset::K => "k",
+            JyutpingOnset::Ng => "ng",
+            JyutpingOnset::H => "h",
+            JyutpingOnset::Gw => "gw",
+            JyutpingOnset::Kw => "kw",
+            JyutpingOnset::W => "w",
+            JyutpingOnset::Z => "z",
+            JyutpingOnset::C => "c",
+            JyutpingOnset::S => "s",
+            JyutpingOnset::J => "j",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum JyutpingNucleus {
+    Aa,
+    A,
+    E,
+    I,
+    O,
+    U,
+    Oe,
+    Eo,
+    Yu,
+    /// Syllabic m, as in `m4` 唔
+    M,
+    /// Syllabic ng, as in `ng5` 五
+    Ng,
+}
+
+impl JyutpingNucleus {
+    /// Longest first, so that `aa` is found before `a`
+    pub const ALL: [JyutpingNucleus; 11] = [
+        JyutpingNucleus::Aa,
+        JyutpingNucleus::Oe,
+        JyutpingNucleus::Eo,
+        JyutpingNucleus::Yu,
+        JyutpingNucleus::Ng,
+        JyutpingNucleus::A,
+        JyutpingNucleus::E,
+        JyutpingNucleus::I,
+        JyutpingNucleus::O,
+        JyutpingNucleus::U,
+        JyutpingNucleus::M,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JyutpingNucleus::Aa => "aa",
+            JyutpingNucleus::A => "a",
+            JyutpingNucleus::E => "e",
+            JyutpingNucleus::I => "i",
+            JyutpingNucleus::O => "o",
+            JyutpingNucleus::U => "u",
+            JyutpingNucleus::Oe => "oe",
+            JyutpingNucleus::Eo => "eo",
+            JyutpingNucleus::Yu => "yu",
+            JyutpingNucleus::M => "m",
+            JyutpingNucleus::Ng => "ng",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum JyutpingCoda {
+    I,
+    U,
+    M,
+    N,
+    Ng,
+    P,
+    T,
+    K,
+}
+
+impl JyutpingCoda {
+    pub const ALL: [JyutpingCoda; 8] = [
+        JyutpingCoda::I,
+        JyutpingCoda::U,
+        JyutpingCoda::M,
+        JyutpingCoda::N,
+        JyutpingCoda::Ng,
+        JyutpingCoda::P,
+        JyutpingCoda::T,
+        JyutpingCoda::K,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JyutpingCoda::I => "i",
+            JyutpingCoda::U => "u",
+            JyutpingCoda::M => "m",
+            JyutpingCoda::N => "n",
+            JyutpingCoda::Ng => "ng",
+            JyutpingCoda::P => "p",
+            JyutpingCoda::T => "t",
+            JyutpingCoda::K => "k",
+        }
+    }
+
+    /// Whether this is one of the unreleased stops -p, -t and -k.
+    pub fn is_stop(self) -> bool {
+        matches!(self, JyutpingCoda::P | JyutpingCoda::T | JyutpingCoda::K)
+    }
+}
+
+impl fmt::Display for PinyinInitial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for PinyinMedial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for PinyinFinal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for JyutpingOnset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for JyutpingNucleus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for JyutpingCoda {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syllable::{JyutpingSyllable, PinyinSyllable};
+
+    fn pinyin(
+        pronunciation: &str,
+    ) -> Option<(Option<PinyinInitial>, Option<PinyinMedial>, PinyinFinal)> {
+        PinyinSyllable::new(pronunciation, "1")
+            .pinyin_parts()
+            .map(|parts| (parts.initial, parts.medial, parts.final_))
+    }
+
+    fn jyutping(
+        pronunciation: &str,
+    ) -> Option<(Option<JyutpingOnset>, JyutpingNucleus, Option<JyutpingCoda>)> {
+        JyutpingSyllable::new(pronunciation, "1")
+            .jyutping_parts()
+            .map(|parts| (parts.onset, parts.nucleus, parts.coda))
+    }
+
+    #[test]
+    fn test_pinyin_parts() {
+        use PinyinFinal as F;
+        use PinyinInitial as I;
+        use PinyinMedial as M;
+
+        assert_eq!(pinyin("Zhuang"), Some((Some(I::Zh), Some(M::U), F::Ang)));
+        assert_eq!(pinyin("zhi"), Some((Some(I::Zh), None, F::Apical)));
+        assert_eq!(pinyin("ji"), Some((Some(I::J), None, F::I)));
+        assert_eq!(pinyin("you"), Some((None, Some(M::I), F::Ou)));
+        assert_eq!(pinyin("xie"), Some((Some(I::X), Some(M::I), F::EHat)));
+        assert_eq!(pinyin("lu:e"), Some((Some(I::L), Some(M::V), F::EHat)));
+        assert_eq!(pinyin("jun"), Some((Some(I::J), None, F::Vn)));
+        assert_eq!(pinyin("dun"), Some((Some(I::D), Some(M::U), F::En)));
+        assert_eq!(pinyin("xiong"), Some((Some(I::X), Some(M::I), F::Ong)));
+        assert_eq!(pinyin("ying"), Some((None, None, F::Ing)));
+        assert_eq!(pinyin("hng"), Some((Some(I::H), None, F::Ng)));
+        assert_eq!(pinyin("r"), Some((None, None, F::R)));
+        assert_eq!(pinyin("blorp"), None);
+    }
+
+    #[test]
+    fn test_jyutping_parts() {
+        use JyutpingCoda as C;
+        use JyutpingNucleus as N;
+        use JyutpingOnset as O;
+
+        assert_eq!(jyutping("gwong"), Some((Some(O::Gw), N::O, Some(C::Ng))));
+        assert_eq!(jyutping("aau"), Some((None, N::Aa, Some(C::U))));
+        assert_eq!(jyutping("heoi"), Some((Some(O::H), N::Eo, Some(C::I))));
+        assert_eq!(jyutping("jyut"), Some((Some(O::J), N::Yu, Some(C::T))));
+        assert_eq!(jyutping("Ngo"), Some((Some(O::Ng), N::O, None)));
+        assert_eq!(jyutping("ng"), Some((None, N::Ng, None)));
+        assert_eq!(jyutping("hm"), Some((Some(O::H), N::M, None)));
+        assert_eq!(jyutping("zhi"), None);
+    }
+
+    #[test]
+    fn test_parts_of_letters() {
+        assert_eq!(PinyinSyllable::letter("A").pinyin_parts(), None);
+        assert_eq!(JyutpingSyllable::punctuation("·").jyutping_parts(), None);
+    }
+
+    #[test]
+    fn test_every_syllable_decomposes() {
+        for syllable in inventory::PINYIN_SYLLABLES {
+            assert!(pinyin(syllable).is_some(), "{}", syllable);
+        }
+
+        for onset in inventory::JYUTPING_INITIALS.iter().chain([""].iter()) {
+            for final_ in inventory::JYUTPING_FINALS {
+                let syllable = format!("{}{}", onset, final_);
+                if inventory::is_jyutping_syllable(&syllable) {
+                    let parts = jyutping(&syllable).unwrap();
+                    let spelled = format!(
+                        "{}{}{}",
+                        parts.0.map_or("", JyutpingOnset::as_str),
+                        parts.1,
+                        parts.2.map_or("", JyutpingCoda::as_str)
+                    );
+                    assert_eq!(spelled, syllable);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,860 @@
+/*!
+Conversions between the numbered pinyin used by CC-CEDICT, e.g. `ni3 hao3`, and tone-marked
+pinyin, e.g. `nǐ hǎo`.
+
+# Usage:
+```
+use cccedict::cedict_entry::CedictEntry;
+use cccedict::pinyin::*;
+use cccedict::syllable::Syllable;
+
+assert_eq!(Syllable::new("lu:e", "4").to_tone_marked(), "lüè");
+
+let entry = CedictEntry::new("你好嗎 你好吗 [ni3 hao3 ma5] /how are you?/").unwrap();
+let pinyin = entry.pinyin.unwrap();
+assert_eq!(pinyin.to_tone_marked(), "nǐ hǎo ma");
+
+let options = ToneMarkOptions {
+    separator: "".into(),
+    ..ToneMarkOptions::default()
+};
+assert_eq!(pinyin.to_tone_marked_with(&options), "nǐhǎoma");
+```
+
+Tone marks follow the standard placement rules: `a` or `e` takes the mark if present, `o` takes
+it in `ou`, and otherwise the last vowel does. Neutral tones are left unmarked. When syllables are
+joined without a separator, an apostrophe is inserted before syllables starting with `a`, `e` or
+`o`, as in `xī'ān`.
+
+Tone-marked input, as typed into a search box, can be parsed back into numbered `Syllable`s with
+`parse_tone_marked`. Unspaced input is split into syllables using the pinyin inventory, taking
+the best of its `segmentations`, and syllables without a tone mark are given an empty tone, like
+toneless syllables in CC-CEDICT brackets:
+
+```
+# use cccedict::pinyin::*;
+# use cccedict::syllable::Syllable;
+assert_eq!(
+    parse_tone_marked("Nǐhǎo ma").unwrap(),
+    vec![
+        Syllable::new("Ni", "3"),
+        Syllable::new("hao", "3"),
+        Syllable::new("ma", ""),
+    ]
+);
+assert_eq!(
+    parse_tone_marked("xī'ān").unwrap(),
+    vec![Syllable::new("xi", "1"), Syllable::new("an", "1")]
+);
+assert_eq!(parse_tone_marked("nǚ").unwrap(), vec![Syllable::new("nu:", "3")]);
+```
+
+Toneless input is often ambiguous. `segmentations` returns every way of splitting it into
+syllables, best first, so that a search for `xianzai` can try `xian zai` before `xi an zai`:
+
+```
+# use cccedict::pinyin::*;
+# use cccedict::syllable::Syllable;
+let segmentations = segmentations("xianzai", 10).unwrap();
+assert_eq!(
+    segmentations[0],
+    vec![Syllable::new("xian", ""), Syllable::new("zai", "")]
+);
+assert_eq!(
+    segmentations[1],
+    vec![
+        Syllable::new("xi", ""),
+        Syllable::new("an", ""),
+        Syllable::new("zai", ""),
+    ]
+);
+```
+*/
+
+use crate::errors::PinyinInputError;
+use crate::inventory;
+use crate::syllable::{Pinyin, Syllable, SyllableKind, SyllableRef};
+use crate::tone::MandarinTone;
+
+/// The Unicode normalization form of tone-marked output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// Precomposed characters where they exist, e.g. `ǎ` as U+01CE
+    #[default]
+    Nfc,
+    /// Base letters followed by combining marks, e.g. `ǎ` as `a` + U+030C
+    Nfd,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToneMarkOptions {
+    /// Placed between syllables. Defaults to a single space.
+    pub separator: String,
+    pub normalization: Normalization,
+}
+
+impl Default for ToneMarkOptions {
+    fn default() -> Self {
+        ToneMarkOptions {
+            separator: " ".into(),
+            normalization: Normalization::Nfc,
+        }
+    }
+}
+
+/// Renders numbered pinyin as tone-marked pinyin.
+pub trait ToneMarked {
+    fn to_tone_marked_with(&self, options: &ToneMarkOptions) -> String;
+
+    fn to_tone_marked(&self) -> String {
+        self.to_tone_marked_with(&ToneMarkOptions::default())
+    }
+}
+
+impl<'a> ToneMarked for SyllableRef<'a, Pinyin> {
+    fn to_tone_marked_with(&self, options: &ToneMarkOptions) -> String {
+        if self.kind != SyllableKind::Syllable {
+            return self.pronunciation.to_string();
+        }
+
+        let letters = letters(self.pronunciation);
+        let marked = self
+            .mandarin_tone()
+            .and_then(|tone| tone_mark(tone).map(|mark| (mark_index(&letters), mark)));
+
+        let mut output = String::new();
+        for (idx, letter) in letters.iter().enumerate() {
+            let tone_mark = match marked {
+                Some((Some(marked_idx), mark)) if marked_idx == idx => Some(mark),
+                _ => None,
+            };
+            push_letter(&mut output, *letter, tone_mark, options.normalization);
+        }
+
+        output
+    }
+}
+
+impl ToneMarked for Syllable<Pinyin> {
+    fn to_tone_marked_with(&self, options: &ToneMarkOptions) -> String {
+        self.as_ref().to_tone_marked_with(options)
+    }
+}
+
+impl<'a> ToneMarked for [SyllableRef<'a, Pinyin>] {
+    fn to_tone_marked_with(&self, options: &ToneMarkOptions) -> String {
+        join(self.iter().copied(), options)
+    }
+}
+
+impl ToneMarked for [Syllable<Pinyin>] {
+    fn to_tone_marked_with(&self, options: &ToneMarkOptions) -> String {
+        join(self.iter().map(Syllable::as_ref), options)
+    }
+}
+
+fn join<'a, I: Iterator<Item = SyllableRef<'a, Pinyin>>>(
+    syllables: I,
+    options: &ToneMarkOptions,
+) -> String {
+    let mut output = String::new();
+
+    for (idx, syllable) in syllables.enumerate() {
+        let marked = syllable.to_tone_marked_with(options);
+
+        if idx > 0 {
+            output.push_str(&options.separator);
+
+            let starts_with_vowel = marked
+                .chars()
+                .next()
+                .is_some_and(|c| "aeoAEOāáǎàēéěèōóǒòĀÁǍÀĒÉĚÈŌÓǑÒ".contains(c));
+            if options.separator.is_empty()
+                && syllable.kind == SyllableKind::Syllable
+                && starts_with_vowel
+            {
+                output.push('\'');
+            }
+        }
+
+        output.push_str(&marked);
+    }
+
+    output
+}
+
+/// A letter of a pinyin syllable, with `u:` and `v` collapsed into a single ü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Letter {
+    base: char,
+    umlaut: bool,
+}
+
+fn letters(pronunciation: &str) -> Vec<Letter> {
+    let mut letters: Vec<Letter> = vec![];
+
+    for c in pronunciation.chars() {
+        match c {
+            ':' if matches!(
+                letters.last(),
+                Some(Letter { base: 'u', .. }) | Some(Letter { base: 'U', .. })
+            ) =>
+            {
+                letters.last_mut().unwrap().umlaut = true;
+            }
+            'v' | 'ü' => letters.push(Letter {
+                base: 'u',
+                umlaut: true,
+            }),
+            'V' | 'Ü' => letters.push(Letter {
+                base: 'U',
+                umlaut: true,
+            }),
+            base => letters.push(Letter {
+                base,
+                umlaut: false,
+            }),
+        }
+    }
+
+    letters
+}
+
+/// Picks the letter that carries the tone mark.
+fn mark_index(letters: &[Letter]) -> Option<usize> {
+    let lower: Vec<char> = letters
+        .iter()
+        .map(|l| l.base.to_ascii_lowercase())
+        .collect();
+    let is_vowel = |c: char| "aeiou".contains(c);
+
+    if let Some(idx) = lower.iter().position(|&c| c == 'a' || c == 'e') {
+        return Some(idx);
+    }
+
+    if let Some(idx) = lower.windows(2).position(|pair| pair == ['o', 'u']) {
+        return Some(idx);
+    }
+
+    if let Some(idx) = lower.iter().rposition(|&c| is_vowel(c)) {
+        return Some(idx);
+    }
+
+    // Syllabic nasals: m, n, ng, hm, hng
+    lower.iter().position(|&c| c == 'm' || c == 'n')
+}
+
+/// The combining character for a tone, or `None` for the unmarked neutral tone.
+fn tone_mark(tone: MandarinTone) -> Option<char> {
+    match tone {
+        MandarinTone::First => Some('\u{304}'),
+        MandarinTone::Second => Some('\u{301}'),
+        MandarinTone::Third => Some('\u{30C}'),
+        MandarinTone::Fourth => Some('\u{300}'),
+        MandarinTone::Neutral => None,
+    }
+}
+
+const DIAERESIS: char = '\u{308}';
+
+fn push_letter(
+    output: &mut String,
+    letter: Letter,
+    tone_mark: Option<char>,
+    normalization: Normalization,
+) {
+    if normalization == Normalization::Nfc {
+        if let Some(composed) = compose(letter, tone_mark) {
+            output.push(composed);
+            return;
+        }
+    }
+
+    output.push(letter.base);
+    if letter.umlaut {
+        output.push(DIAERESIS);
+    }
+    if let Some(mark) = tone_mark {
+        output.push(mark);
+    }
+}
+
+/// The precomposed form of a letter with its marks, if Unicode has one.
+fn compose(letter: Letter, tone_mark: Option<char>) -> Option<char> {
+    const MARKS: [Option<char>; 5] = [
+        None,
+        Some('\u{304}'),
+        Some('\u{301}'),
+        Some('\u{30C}'),
+        Some('\u{300}'),
+    ];
+    let column = MARKS.iter().position(|mark| *mark == tone_mark)?;
+
+    let row: [char; 5] = match (letter.base, letter.umlaut) {
+        ('a', false) => ['a', 'ā', 'á', 'ǎ', 'à'],
+        ('e', false) => ['e', 'ē', 'é', 'ě', 'è'],
+        ('i', false) => ['i', 'ī', 'í', 'ǐ', 'ì'],
+        ('o', false) => ['o', 'ō', 'ó', 'ǒ', 'ò'],
+        ('u', false) => ['u', 'ū', 'ú', 'ǔ', 'ù'],
+        ('u', true) => ['ü', 'ǖ', 'ǘ', 'ǚ', 'ǜ'],
+        ('A', false) => ['A', 'Ā', 'Á', 'Ǎ', 'À'],
+        ('E', false) => ['E', 'Ē', 'É', 'Ě', 'È'],
+        ('I', false) => ['I', 'Ī', 'Í', 'Ǐ', 'Ì'],
+        ('O', false) => ['O', 'Ō', 'Ó', 'Ǒ', 'Ò'],
+        ('U', false) => ['U', 'Ū', 'Ú', 'Ǔ', 'Ù'],
+        ('U', true) => ['Ü', 'Ǖ', 'Ǘ', 'Ǚ', 'Ǜ'],
+        ('m', false) => ['m', '\0', 'ḿ', '\0', '\0'],
+        ('n', false) => ['n', '\0', 'ń', 'ň', 'ǹ'],
+        ('M', false) => ['M', '\0', 'Ḿ', '\0', '\0'],
+        ('N', false) => ['N', '\0', 'Ń', 'Ň', 'Ǹ'],
+        (base, false) if tone_mark.is_none() => return Some(base),
+        _ => return None,
+    };
+
+    Some(row[column]).filter(|c| *c != '\0')
+}
+
+/// Parses tone-marked pinyin such as `nǐhǎo`, `Nǐ hǎo` or `xī'ān` into numbered `Syllable`s.
+///
+/// Syllables may be separated by whitespace, apostrophes or hyphens, or not at all. ü may be
+/// written as `ü`, `v` or `u:`, and tone numbers are accepted in place of tone marks, so
+/// `ni3hao3` parses too. Both precomposed and combining tone marks are understood.
+pub fn parse_tone_marked(input: &str) -> Result<Vec<Syllable<Pinyin>>, PinyinInputError> {
+    let mut syllables = vec![];
+
+    for chunk in chunks(input)? {
+        let lengths = segment(&chunk.letters, 1)
+            .into_iter()
+            .next()
+            .ok_or_else(|| PinyinInputError::new(input, chunk.letters[0].offset))?;
+        syllables.extend(chunk.syllables(&lengths.lengths));
+    }
+
+    Ok(syllables)
+}
+
+/// Returns up to `limit` ways of splitting pinyin input into syllables, best first.
+///
+/// Input is read as in `parse_tone_marked`, and apostrophes and spaces always end a syllable, so
+/// `xi'an` has a single segmentation. Pinyin writes an apostrophe before a syllable starting with
+/// `a`, `e` or `o` that follows another, so segmentations that would have needed one rank lowest,
+/// which reads `fangan` as `fan gan`. Otherwise segmentations with fewer syllables rank higher, and
+/// ties go to the one whose earlier syllables are longer. An error is returned if any part of the
+/// input can't be split at all.
+pub fn segmentations(
+    input: &str,
+    limit: usize,
+) -> Result<Vec<Vec<Syllable<Pinyin>>>, PinyinInputError> {
+    let mut segmentations: Vec<(Rank, Vec<Syllable<Pinyin>>)> = vec![(Rank::default(), vec![])];
+
+    for chunk in chunks(input)? {
+        let options: Vec<(Rank, Vec<Syllable<Pinyin>>)> = segment(&chunk.letters, limit.max(1))
+            .into_iter()
+            .map(|segmentation| (segmentation.rank(), chunk.syllables(&segmentation.lengths)))
+            .collect();
+        if options.is_empty() {
+            return Err(PinyinInputError::new(input, chunk.letters[0].offset));
+        }
+
+        // Only the best `limit` of each chunk can make it into the best `limit` overall
+        segmentations = segmentations
+            .iter()
+            .flat_map(|(prefix_rank, prefix)| {
+                options.iter().map(move |(rank, option)| {
+                    (prefix_rank.add(rank), [prefix.as_slice(), option].concat())
+                })
+            })
+            .collect();
+        // Stable, so equally ranked segmentations keep the per-chunk ranking
+        segmentations.sort_by_key(|(rank, _)| *rank);
+        segmentations.truncate(limit);
+    }
+
+    Ok(segmentations
+        .into_iter()
+        .map(|(_, segmentation)| segmentation)
+        .collect())
+}
+
+/// A letter of pinyin input with its tone mark removed. ü is kept as `ü` or `Ü`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct InputLetter {
+    letter: char,
+    tone: Option<MandarinTone>,
+    /// Byte offset in the input
+    offset: usize,
+}
+
+/// A run of letters between separators, with the tone number that ended it, if any.
+#[derive(Debug, Default)]
+struct Chunk {
+    letters: Vec<InputLetter>,
+    tone: Option<MandarinTone>,
+}
+
+impl Chunk {
+    /// Builds syllables of the given lengths. A tone number applies to the last syllable.
+    fn syllables(&self, lengths: &[usize]) -> Vec<Syllable<Pinyin>> {
+        let mut start = 0;
+
+        lengths
+            .iter()
+            .enumerate()
+            .map(|(idx, len)| {
+                let letters = &self.letters[start..start + len];
+                start += len;
+
+                let marked_tone = letters.iter().find_map(|letter| letter.tone);
+                let tone = match (marked_tone, self.tone) {
+                    (Some(tone), _) => Some(tone),
+                    (None, tone) if idx == lengths.len() - 1 => tone,
+                    _ => None,
+                };
+
+                Syllable::new(
+                    &spelling(letters),
+                    &tone.map_or(String::new(), |tone| tone.to_string()),
+                )
+            })
+            .collect()
+    }
+}
+
+fn chunks(input: &str) -> Result<Vec<Chunk>, PinyinInputError> {
+    let mut chunks = vec![];
+    let mut chunk = Chunk::default();
+
+    for (offset, c) in input.char_indices() {
+        let error = || PinyinInputError::new(input, offset);
+
+        match c {
+            c if c.is_whitespace() || c == '\'' || c == '’' || c == '-' => {
+                if !chunk.letters.is_empty() {
+                    chunks.push(std::mem::take(&mut chunk));
+                }
+            }
+            '1'..='5' => {
+                if chunk.letters.is_empty() {
+                    return Err(error());
+                }
+                chunk.tone = MandarinTone::from_number(c as u8 - b'0');
+                chunks.push(std::mem::take(&mut chunk));
+            }
+            ':' | DIAERESIS => match chunk.letters.last_mut() {
+                Some(last) if last.letter == 'u' || last.letter == 'U' => {
+                    last.letter = if last.letter == 'u' { 'ü' } else { 'Ü' };
+                }
+                _ => return Err(error()),
+            },
+            '\u{304}' | '\u{301}' | '\u{30C}' | '\u{300}' => match chunk.letters.last_mut() {
+                Some(last) if last.tone.is_none() => last.tone = combining_tone(c),
+                _ => return Err(error()),
+            },
+            c => {
+                let (letter, tone) = decompose(c).ok_or_else(error)?;
+                chunk.letters.push(InputLetter {
+                    letter,
+                    tone,
+                    offset,
+                });
+            }
+        }
+    }
+
+    if !chunk.letters.is_empty() {
+        chunks.push(chunk);
+    }
+
+    Ok(chunks)
+}
+
+/// How a segmentation ranks, lowest first: by syllables that would have needed an apostrophe,
+/// then by number of syllables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct Rank {
+    missing_apostrophes: usize,
+    syllables: usize,
+}
+
+impl Rank {
+    fn add(&self, other: &Rank) -> Rank {
+        Rank {
+            missing_apostrophes: self.missing_apostrophes + other.missing_apostrophes,
+            syllables: self.syllables + other.syllables,
+        }
+    }
+}
+
+/// The syllable lengths of one way of splitting a chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Segmentation {
+    missing_apostrophes: usize,
+    lengths: Vec<usize>,
+}
+
+impl Segmentation {
+    fn rank(&self) -> Rank {
+        Rank {
+            missing_apostrophes: self.missing_apostrophes,
+            syllables: self.lengths.len(),
+        }
+    }
+}
+
+/// Finds the best `limit` ways of splitting `letters` into pinyin syllables, ranked as in
+/// `segmentations` and allowing at most one tone mark per syllable.
+///
+/// Works back from the end of the input, keeping only the best `limit` segmentations of each
+/// suffix, which is enough because a segmentation ranks by its first syllable and then by the
+/// rank of the rest.
+fn segment(letters: &[InputLetter], limit: usize) -> Vec<Segmentation> {
+    let mut best: Vec<Vec<Segmentation>> = vec![vec![]; letters.len() + 1];
+    best[letters.len()] = vec![Segmentation {
+        missing_apostrophes: 0,
+        lengths: vec![],
+    }];
+
+    for start in (0..letters.len()).rev() {
+        let needs_apostrophe = start > 0 && "aeoAEO".contains(letters[start].letter);
+        let longest = (letters.len() - start).min(MAX_SYLLABLE_LENGTH);
+
+        let mut found = vec![];
+        for len in 1..=longest {
+            if !is_syllable(letters, start, start + len) {
+                continue;
+            }
+
+            for rest in &best[start + len] {
+                let mut lengths = Vec::with_capacity(rest.lengths.len() + 1);
+                lengths.push(len);
+                lengths.extend(&rest.lengths);
+                found.push(Segmentation {
+                    missing_apostrophes: rest.missing_apostrophes + usize::from(needs_apostrophe),
+                    lengths,
+                });
+            }
+        }
+
+        found.sort_by(|a, b| {
+            a.rank()
+                .cmp(&b.rank())
+                .then_with(|| b.lengths.cmp(&a.lengths))
+        });
+        found.truncate(limit);
+        best[start] = found;
+    }
+
+    std::mem::take(&mut best[0])
+}
+
+/// Whether `letters[start..end]` can be a syllable. Vowelless interjections like `m` and `ng`
+/// have to stand alone, except for erhua `r`, which may end a run of syllables.
+fn is_syllable(letters: &[InputLetter], start: usize, end: usize) -> bool {
+    let candidate = &letters[start..end];
+    let spelling = spelling(candidate);
+
+    let has_vowel = candidate.iter().any(|l| "aeiouüAEIOUÜ".contains(l.letter));
+    let stands_alone = candidate.len() == letters.len()
+        || (spelling.eq_ignore_ascii_case("r") && start > 0 && end == letters.len());
+
+    candidate.iter().filter(|l| l.tone.is_some()).count() <= 1
+        && (has_vowel || stands_alone)
+        && inventory::is_pinyin_syllable(&spelling)
+}
+
+/// The longest pinyin syllables, such as `zhuang`, have six letters
+const MAX_SYLLABLE_LENGTH: usize = 6;
+
+/// Spells letters the way CC-CEDICT does: ü is `u` after j, q, x and y, and `u:` otherwise.
+fn spelling(letters: &[InputLetter]) -> String {
+    let after_palatal = letters
+        .first()
+        .is_some_and(|l| "jqxyJQXY".contains(l.letter));
+
+    letters
+        .iter()
+        .map(|l| match l.letter {
+            'ü' if after_palatal => "u".to_string(),
+            'Ü' if after_palatal => "U".to_string(),
+            'ü' => "u:".to_string(),
+            'Ü' => "U:".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Splits a possibly tone-marked character into its base letter and tone.
+fn decompose(c: char) -> Option<(char, Option<MandarinTone>)> {
+    match c {
+        'v' | 'ü' => return Some(('ü', None)),
+        'V' | 'Ü' => return Some(('Ü', None)),
+        c if c.is_ascii_alphabetic() => return Some((c, None)),
+        _ => {}
+    }
+
+    const BASES: [(char, bool); 16] = [
+        ('a', false),
+        ('e', false),
+        ('i', false),
+        ('o', false),
+        ('u', false),
+        ('u', true),
+        ('m', false),
+        ('n', false),
+        ('A', false),
+        ('E', false),
+        ('I', false),
+        ('O', false),
+        ('U', false),
+        ('U', true),
+        ('M', false),
+        ('N', false),
+    ];
+
+    BASES.iter().find_map(|&(base, umlaut)| {
+        (1..=4).find_map(|number| {
+            let tone = MandarinTone::from_number(number)?;
+            if compose(Letter { base, umlaut }, tone_mark(tone)) != Some(c) {
+                return None;
+            }
+
+            let letter = match (base, umlaut) {
+                ('u', true) => 'ü',
+                ('U', true) => 'Ü',
+                (base, _) => base,
+            };
+            Some((letter, Some(tone)))
+        })
+    })
+}
+
+fn combining_tone(mark: char) -> Option<MandarinTone> {
+    (1..=4)
+        .filter_map(MandarinTone::from_number)
+        .find(|tone| tone_mark(*tone) == Some(mark))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marked(pronunciation: &str, tone: &str) -> String {
+        Syllable::new(pronunciation, tone).to_tone_marked()
+    }
+
+    #[test]
+    fn test_mark_placement() {
+        assert_eq!(marked("hao", "3"), "hǎo");
+        assert_eq!(marked("xie", "4"), "xiè");
+        assert_eq!(marked("zhou", "1"), "zhōu");
+        assert_eq!(marked("gui", "4"), "guì");
+        assert_eq!(marked("liu", "2"), "liú");
+        assert_eq!(marked("er", "4"), "èr");
+        assert_eq!(marked("Zhong", "1"), "Zhōng");
+        assert_eq!(marked("An", "1"), "Ān");
+    }
+
+    #[test]
+    fn test_umlauts() {
+        assert_eq!(marked("lu:", "4"), "lǜ");
+        assert_eq!(marked("nv", "3"), "nǚ");
+        assert_eq!(marked("lu:e", "4"), "lüè");
+        assert_eq!(marked("lu:", "5"), "lü");
+        assert_eq!(marked("ju", "2"), "jú");
+    }
+
+    #[test]
+    fn test_neutral_and_missing_tones() {
+        assert_eq!(marked("ma", "5"), "ma");
+        assert_eq!(marked("ma", ""), "ma");
+        assert_eq!(Syllable::letter("A").to_tone_marked(), "A");
+        assert_eq!(Syllable::punctuation("·").to_tone_marked(), "·");
+    }
+
+    #[test]
+    fn test_syllabic_nasals() {
+        assert_eq!(marked("m", "2"), "ḿ");
+        assert_eq!(marked("ng", "3"), "ňg");
+        assert_eq!(marked("hng", "4"), "hǹg");
+        assert_eq!(marked("m", "1"), "m\u{304}");
+    }
+
+    #[test]
+    fn test_nfd() {
+        let options = ToneMarkOptions {
+            normalization: Normalization::Nfd,
+            ..ToneMarkOptions::default()
+        };
+
+        assert_eq!(
+            Syllable::new("hao", "3").to_tone_marked_with(&options),
+            "ha\u{30C}o"
+        );
+        assert_eq!(
+            Syllable::new("lu:", "4").to_tone_marked_with(&options),
+            "lu\u{308}\u{300}"
+        );
+    }
+
+    #[test]
+    fn test_parse_tone_marked() {
+        assert_eq!(
+            parse_tone_marked("nǐhǎo").unwrap(),
+            vec![Syllable::new("ni", "3"), Syllable::new("hao", "3")]
+        );
+        assert_eq!(
+            parse_tone_marked("  Běijīng ").unwrap(),
+            vec![Syllable::new("Bei", "3"), Syllable::new("jing", "1")]
+        );
+        assert_eq!(
+            parse_tone_marked("nǎr").unwrap(),
+            vec![Syllable::new("na", "3"), Syllable::new("r", "")]
+        );
+    }
+
+    #[test]
+    fn test_parse_tone_marked_umlauts() {
+        assert_eq!(
+            parse_tone_marked("lǜ lüè lv4 nu:3 qù").unwrap(),
+            vec![
+                Syllable::new("lu:", "4"),
+                Syllable::new("lu:e", "4"),
+                Syllable::new("lu:", "4"),
+                Syllable::new("nu:", "3"),
+                Syllable::new("qu", "4"),
+            ]
+        );
+        assert_eq!(
+            parse_tone_marked("xǘ").unwrap(),
+            vec![Syllable::new("xu", "2")]
+        );
+    }
+
+    #[test]
+    fn test_parse_tone_marked_separators() {
+        let expected = vec![Syllable::new("xi", "1"), Syllable::new("an", "1")];
+
+        assert_eq!(parse_tone_marked("xī'ān").unwrap(), expected);
+        assert_eq!(parse_tone_marked("xī’ān").unwrap(), expected);
+        assert_eq!(parse_tone_marked("xī-ān").unwrap(), expected);
+        assert_eq!(
+            parse_tone_marked("xiān").unwrap(),
+            vec![Syllable::new("xian", "1")]
+        );
+    }
+
+    #[test]
+    fn test_parse_tone_marked_nfd_and_numbers() {
+        assert_eq!(
+            parse_tone_marked("ni\u{30C}ha\u{30C}o").unwrap(),
+            vec![Syllable::new("ni", "3"), Syllable::new("hao", "3")]
+        );
+        assert_eq!(
+            parse_tone_marked("ni3hao3 ma").unwrap(),
+            vec![
+                Syllable::new("ni", "3"),
+                Syllable::new("hao", "3"),
+                Syllable::new("ma", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tone_marked_round_trip() {
+        let syllables = parse_tone_marked("Zhōngguó rénmín yínháng").unwrap();
+        assert_eq!(syllables.to_tone_marked(), "Zhōng guó rén mín yín háng");
+    }
+
+    #[test]
+    fn test_parse_tone_marked_errors() {
+        assert_eq!(
+            parse_tone_marked("nǐ hǎo!"),
+            Err(PinyinInputError::new("nǐ hǎo!", 8))
+        );
+        assert_eq!(
+            parse_tone_marked("blorp"),
+            Err(PinyinInputError::new("blorp", 0))
+        );
+        assert!(parse_tone_marked("3").is_err());
+        assert_eq!(
+            parse_tone_marked("fǎngǎn").unwrap(),
+            vec![Syllable::new("fan", "3"), Syllable::new("gan", "3")]
+        );
+    }
+
+    #[test]
+    fn test_segmentations() {
+        let toneless = |pronunciations: &[&str]| -> Vec<Syllable<Pinyin>> {
+            pronunciations
+                .iter()
+                .map(|p| Syllable::new(p, ""))
+                .collect()
+        };
+
+        assert_eq!(
+            segmentations("nihao", 10).unwrap(),
+            vec![toneless(&["ni", "hao"]), toneless(&["ni", "ha", "o"])]
+        );
+        // An `an` after another syllable would have been written `fang'an`
+        assert_eq!(
+            segmentations("fangan", 10).unwrap(),
+            vec![toneless(&["fan", "gan"]), toneless(&["fang", "an"])]
+        );
+        assert_eq!(
+            segmentations("xi'an", 10).unwrap(),
+            vec![toneless(&["xi", "an"])]
+        );
+        assert_eq!(
+            segmentations("xian zai", 10).unwrap(),
+            vec![toneless(&["xian", "zai"]), toneless(&["xi", "an", "zai"])]
+        );
+        assert_eq!(
+            segmentations("nar", 10).unwrap(),
+            vec![toneless(&["na", "r"])]
+        );
+        assert_eq!(segmentations("ng", 10).unwrap(), vec![toneless(&["ng"])]);
+        assert!(segmentations("xianzaiq", 10).is_err());
+    }
+
+    #[test]
+    fn test_segmentations_limit() {
+        assert_eq!(segmentations("xian zai", 1).unwrap().len(), 1);
+        assert!(segmentations("nihao", 0).unwrap().is_empty());
+
+        // Every `xian` can be split two ways, which would be 2^64 segmentations in all
+        let input = "xian".repeat(64);
+        let best = segmentations(&input, 5).unwrap();
+        assert_eq!(best.len(), 5);
+        assert_eq!(best[0], vec![Syllable::new("xian", ""); 64]);
+    }
+
+    #[test]
+    fn test_segmentations_with_tones() {
+        assert_eq!(
+            segmentations("xīān", 10).unwrap(),
+            vec![vec![Syllable::new("xi", "1"), Syllable::new("an", "1")]]
+        );
+        assert_eq!(
+            segmentations("xian1", 10).unwrap()[0],
+            vec![Syllable::new("xian", "1")]
+        );
+    }
+
+    #[test]
+    fn test_separators() {
+        let syllables = [Syllable::new("Xi", "1"), Syllable::new("an", "1")];
+        assert_eq!(syllables.to_tone_marked(), "Xī ān");
+
+        let options = ToneMarkOptions {
+            separator: "".into(),
+            ..ToneMarkOptions::default()
+        };
+        assert_eq!(syllables.to_tone_marked_with(&options), "Xī'ān");
+
+        let options = ToneMarkOptions {
+            separator: "-".into(),
+            ..ToneMarkOptions::default()
+        };
+        assert_eq!(syllables.to_tone_marked_with(&options), "Xī-ān");
+    }
+}
@@ -0,0 +1,286 @@
+/*!
+A `PrefixIndex` is a trie over the ways an entry can be typed: its traditional and simplified
+headwords and its pinyin and jyutping without tones, so that a `Cedict` can suggest entries for
+a partial query like `你` or `nih`.
+
+Readings are keyed the way `crate::reading_index` keys them, in lowercase with their spaces, tones
+and punctuation removed, and queries are normalized the same way before they are looked up. Only
+letters and characters are kept, so `ni3 h` is the same prefix as `nih`.
+
+Suggestions have to be fast enough to run on every keystroke, even for a one-letter prefix that
+matches much of the dictionary. The trie keeps the best few entries under each busy node, ranked
+with shorter headwords first, so `Cedict::suggest` doesn't rank every match. Other rankings, given
+to `Cedict::suggest_by_key`, still have to.
+
+# Usage:
+```
+use cccedict::cedict::Cedict;
+use std::str::FromStr;
+
+let cedict = Cedict::from_str("\
+你好 你好 [ni3 hao3] {nei5 hou2} /hello/
+你 你 [ni3] {nei5} /you/
+泥 泥 [ni2] {nai4} /mud/").unwrap();
+
+let headwords = |entries: Vec<&cccedict::cedict::CedictEntry>| -> Vec<String> {
+    entries.iter().map(|entry| entry.simplified.clone()).collect()
+};
+
+// Shorter headwords are suggested first
+assert_eq!(headwords(cedict.suggest("ni", 10)), vec!["你", "泥", "你好"]);
+assert_eq!(headwords(cedict.suggest("你", 10)), vec!["你", "你好"]);
+assert_eq!(headwords(cedict.suggest("nih", 10)), vec!["你好"]);
+assert_eq!(headwords(cedict.suggest("nei", 1)), vec!["你"]);
+
+// Any ranking can be used instead, here longer headwords first
+let ranked = cedict.suggest_by_key("ni", 2, |entry| {
+    std::cmp::Reverse(entry.simplified.chars().count())
+});
+assert_eq!(headwords(ranked), vec!["你好", "你"]);
+```
+*/
+
+use crate::cedict_entry::CedictEntry;
+use crate::reading_index;
+
+/// How many of the best suggestions are kept at each node whose keys match more entries than that,
+/// so that short prefixes don't have to rank every entry they match.
+pub const RANKED_PER_NODE: usize = 32;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Children sorted by the character leading to them
+    children: Vec<(char, usize)>,
+    /// Positions of the entries with a key ending at this node
+    positions: Vec<usize>,
+    /// The best `RANKED_PER_NODE` positions under this node, for nodes with more than that
+    ranked: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PrefixIndex {
+    nodes: Vec<Node>,
+}
+
+impl Default for PrefixIndex {
+    fn default() -> Self {
+        PrefixIndex {
+            nodes: vec![Node::default()],
+        }
+    }
+}
+
+impl PrefixIndex {
+    pub fn new(entries: &[CedictEntry]) -> Self {
+        let mut index = PrefixIndex::default();
+        for (position, entry) in entries.iter().enumerate() {
+            for key in keys(entry) {
+                index.insert(&key, position);
+            }
+        }
+        index.rank(entries);
+        index
+    }
+
+    fn insert(&mut self, key: &str, position: usize) {
+        let mut node = 0;
+        for c in key.chars() {
+            node = match self.child(node, c) {
+                Ok(child) => child,
+                Err(slot) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.insert(slot, (c, child));
+                    child
+                }
+            };
+        }
+
+        let positions = &mut self.nodes[node].positions;
+        if positions.last() != Some(&position) {
+            positions.push(position);
+        }
+    }
+
+    /// Stores the best suggestions at every node that matches too many entries to rank quickly.
+    /// Children are always created after their parent, so going backwards through the nodes
+    /// visits every child before its parent, and each node only merges its children's bests.
+    fn rank(&mut self, entries: &[CedictEntry]) {
+        let mut best: Vec<(Vec<usize>, bool)> = vec![(vec![], false); self.nodes.len()];
+
+        for node in (0..self.nodes.len()).rev() {
+            let mut merged = self.nodes[node].positions.clone();
+            let mut truncated = false;
+            for (_, child) in &self.nodes[node].children {
+                let (child_best, child_truncated) = std::mem::take(&mut best[*child]);
+                merged.extend(child_best);
+                truncated |= child_truncated;
+            }
+            merged.sort_unstable_by_key(|position| rank(entries, *position));
+            merged.dedup();
+
+            if merged.len() > RANKED_PER_NODE {
+                merged.truncate(RANKED_PER_NODE);
+                truncated = true;
+            }
+            if truncated {
+                self.nodes[node].ranked = Some(merged.clone());
+            }
+            best[node] = (merged, truncated);
+        }
+    }
+
+    /// The child of `node` reached by `c`, or where it would be inserted.
+    fn child(&self, node: usize, c: char) -> Result<usize, usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by_key(&c, |(child_char, _)| *child_char)
+            .map(|found| children[found].1)
+    }
+
+    /// The node reached by `prefix` once it is normalized. An empty prefix reaches nothing.
+    fn node(&self, prefix: &str) -> Option<usize> {
+        let prefix = reading_index::key(prefix);
+        if prefix.is_empty() {
+            return None;
+        }
+
+        prefix
+            .chars()
+            .try_fold(0, |node, c| self.child(node, c).ok())
+    }
+
+    fn positions_under(&self, node: usize) -> Vec<usize> {
+        let mut positions = vec![];
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            positions.extend(&self.nodes[node].positions);
+            stack.extend(self.nodes[node].children.iter().map(|(_, child)| *child));
+        }
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// Positions of the entries with a key starting with `prefix`, in file order. An empty prefix
+    /// finds nothing.
+    pub fn find(&self, prefix: &str) -> Vec<usize> {
+        self.node(prefix)
+            .map_or_else(Vec::new, |node| self.positions_under(node))
+    }
+
+    /// Positions of up to `limit` entries with a key starting with `prefix`, those with shorter
+    /// simplified headwords first and otherwise in file order. `entries` must be the entries the
+    /// index was built from. Up to `RANKED_PER_NODE` suggestions are read straight from the trie,
+    /// while longer lists rank every match.
+    pub fn suggest(&self, prefix: &str, limit: usize, entries: &[CedictEntry]) -> Vec<usize> {
+        let node = match self.node(prefix) {
+            Some(node) => node,
+            None => return vec![],
+        };
+
+        let mut positions = match &self.nodes[node].ranked {
+            Some(ranked) if limit <= ranked.len() => ranked[..limit].to_vec(),
+            _ => {
+                let mut positions = self.positions_under(node);
+                positions.sort_by_key(|position| rank(entries, *position));
+                positions
+            }
+        };
+        positions.truncate(limit);
+        positions
+    }
+}
+
+/// Shorter simplified headwords rank first, then earlier entries.
+fn rank(entries: &[CedictEntry], position: usize) -> (usize, usize) {
+    let length = entries
+        .get(position)
+        .map_or(usize::MAX, |entry| entry.simplified.chars().count());
+    (length, position)
+}
+
+/// The keys `entry` can be found by: its headwords and its readings without tones.
+pub fn keys(entry: &CedictEntry) -> Vec<String> {
+    let mut keys = vec![
+        reading_index::key(&entry.traditional),
+        reading_index::key(&entry.simplified),
+    ];
+    if let Some(reading) = &entry.pinyin {
+        keys.push(reading_index::reading_key(reading));
+    }
+    if let Some(reading) = &entry.jyutping {
+        keys.push(reading_index::reading_key(reading));
+    }
+    keys.retain(|key| !key.is_empty());
+    keys.sort_unstable();
+    keys.dedup();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        let entry = CedictEntry::new("AA制 AA制 [A A zhi4] /to split the bill/").unwrap();
+        assert_eq!(keys(&entry), vec!["aazhi", "aa制"]);
+
+        let entry = CedictEntry::new("綠 绿 [lu:4] {luk6} /green/").unwrap();
+        assert_eq!(keys(&entry), vec!["luk", "lü", "綠", "绿"]);
+    }
+
+    #[test]
+    fn test_find() {
+        let entries = vec![
+            CedictEntry::new("你好 你好 [ni3 hao3] /hello/").unwrap(),
+            CedictEntry::new("銀行 银行 [yin2 hang2] /bank/").unwrap(),
+            CedictEntry::new("𠌥 𠆿 [] {wu1} /to stoop/").unwrap(),
+            CedictEntry::new("你 你 [ni3] /you/").unwrap(),
+        ];
+        let index = PrefixIndex::new(&entries);
+
+        assert_eq!(index.find("ni"), vec![0, 3]);
+        assert_eq!(index.find("Ni3 h"), vec![0]);
+        assert_eq!(index.find("銀"), vec![1]);
+        assert_eq!(index.find("银行"), vec![1]);
+        assert_eq!(index.find("w"), vec![2]);
+        assert!(index.find("nihaoma").is_empty());
+        assert!(index.find("").is_empty());
+    }
+
+    #[test]
+    fn test_suggest_beyond_ranked_per_node() {
+        // Entries with longer headwords come first in the file, so ranking has to reorder them
+        let mut entries: Vec<CedictEntry> = (0..RANKED_PER_NODE * 2)
+            .map(|n| {
+                let headword = "你".repeat(RANKED_PER_NODE * 2 - n);
+                CedictEntry::new(&format!("{0} {0} [] /{1}/", headword, n)).unwrap()
+            })
+            .collect();
+        entries.push(CedictEntry::new("好 好 [hao3] /good/").unwrap());
+        let index = PrefixIndex::new(&entries);
+
+        let walked = |limit| {
+            let mut positions = index.find("你");
+            positions.sort_by_key(|position| rank(&entries, *position));
+            positions.truncate(limit);
+            positions
+        };
+
+        for limit in [0, 1, 5, RANKED_PER_NODE, RANKED_PER_NODE + 1, 1000] {
+            assert_eq!(index.suggest("你", limit, &entries), walked(limit));
+        }
+        assert_eq!(
+            index.suggest("你", 3, &entries),
+            vec![
+                RANKED_PER_NODE * 2 - 1,
+                RANKED_PER_NODE * 2 - 2,
+                RANKED_PER_NODE * 2 - 3
+            ]
+        );
+        assert_eq!(index.suggest("hao", 3, &entries), vec![RANKED_PER_NODE * 2]);
+        assert!(index.suggest("", 3, &entries).is_empty());
+    }
+}
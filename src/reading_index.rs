@@ -0,0 +1,212 @@
+/*!
+A `ReadingIndex` maps the readings of entries to the entries that have them, so that a `Cedict` can
+be searched by reading. Readings are keyed by their letters alone, without tones,
+spaces or capitals, so a query only needs to spell the syllables right to find its candidates.
+`matches` then checks the tones that the query gives.
+
+Tones are optional syllable by syllable: a syllable with a tone number only matches that tone, and
+one without matches any tone. In pinyin, the neutral tone `5` also matches syllables with no tone
+at all, and ü can be written `ü`, `u:` or `v`.
+
+# Usage:
+```
+use cccedict::cedict::Cedict;
+use std::str::FromStr;
+
+let cedict = Cedict::from_str("\
+你好 你好 [ni3 hao3] {nei5 hou2} /hello/
+女 女 [nu:3] /female/").unwrap();
+
+for query in ["ni3 hao3", "nihao", "ni hao3", "Ni3hao"] {
+    assert_eq!(cedict.search_pinyin(query)[0].simplified, "你好");
+}
+assert!(cedict.search_pinyin("ni3 hao4").is_empty());
+assert_eq!(cedict.search_pinyin("nv3")[0].simplified, "女");
+assert_eq!(cedict.search_pinyin("nǚ")[0].simplified, "女");
+
+assert_eq!(cedict.search_jyutping("neihou")[0].simplified, "你好");
+assert_eq!(cedict.search_jyutping("nei5hou2")[0].simplified, "你好");
+```
+*/
+
+use crate::syllable::{Romanization, Syllable, SyllableKind};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct ReadingIndex {
+    keys: HashMap<String, Vec<usize>>,
+}
+
+impl ReadingIndex {
+    /// Adds `reading`, the pinyin or jyutping of the entry found at `position` in the entry list.
+    pub fn push<R: Romanization>(&mut self, position: usize, reading: &[Syllable<R>]) {
+        let key = reading_key(reading);
+        if !key.is_empty() {
+            self.keys.entry(key).or_default().push(position);
+        }
+    }
+
+    /// Positions of the entries whose reading is spelled like `query`, ignoring tones, spaces
+    /// and case, in file order.
+    pub fn candidates(&self, query: &str) -> &[usize] {
+        self.keys.get(&key(query)).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Whether `query` spells out `reading`, with tones that agree wherever the query gives them.
+/// Spaces, apostrophes and hyphens in the query are ignored, as is punctuation in the reading.
+pub fn matches<R: Romanization>(query: &str, reading: &[Syllable<R>]) -> bool {
+    let query: Vec<char> = normalize(query).chars().collect();
+    let mut position = 0;
+
+    for syllable in spoken(reading) {
+        skip_separators(&query, &mut position);
+
+        for letter in normalize(&syllable.pronunciation).chars() {
+            if query.get(position) != Some(&letter) {
+                return false;
+            }
+            position += 1;
+        }
+
+        if let Some(digit) = query.get(position).filter(|c| c.is_ascii_digit()) {
+            if !tones_agree::<R>(*digit, &syllable.tone) {
+                return false;
+            }
+            position += 1;
+        }
+    }
+
+    skip_separators(&query, &mut position);
+    position == query.len() && position > 0
+}
+
+pub(crate) fn tones_agree<R: Romanization>(digit: char, tone: &str) -> bool {
+    let mut digit_buffer = [0; 4];
+    let digit: &str = digit.encode_utf8(&mut digit_buffer);
+
+    tone == digit || (tone.is_empty() && R::UNMARKED_TONE == Some(digit))
+}
+
+fn skip_separators(query: &[char], position: &mut usize) {
+    while query
+        .get(*position)
+        .is_some_and(|c| c.is_whitespace() || matches!(c, '\'' | '’' | '-'))
+    {
+        *position += 1;
+    }
+}
+
+/// The syllables and letters of `reading`, without its punctuation.
+pub(crate) fn spoken<R>(reading: &[Syllable<R>]) -> impl Iterator<Item = &Syllable<R>> {
+    reading
+        .iter()
+        .filter(|syllable| syllable.kind != SyllableKind::Punctuation)
+}
+
+pub(crate) fn reading_key<R>(reading: &[Syllable<R>]) -> String {
+    spoken(reading)
+        .map(|syllable| normalize(&syllable.pronunciation))
+        .collect()
+}
+
+pub(crate) fn key(query: &str) -> String {
+    normalize(query)
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .collect()
+}
+
+/// Lowercases `text` and writes every spelling of ü as `ü`.
+pub(crate) fn normalize(text: &str) -> String {
+    text.to_lowercase().replace("u:", "ü").replace('v', "ü")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syllable::{JyutpingSyllable, PinyinSyllable};
+
+    fn pinyin(syllables: &[(&str, &str)]) -> Vec<PinyinSyllable> {
+        syllables
+            .iter()
+            .map(|(pronunciation, tone)| PinyinSyllable::new(pronunciation, tone))
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_optional_tones() {
+        let reading = pinyin(&[("ni", "3"), ("hao", "3")]);
+
+        assert!(matches("ni3 hao3", &reading));
+        assert!(matches("nihao", &reading));
+        assert!(matches("ni hao3", &reading));
+        assert!(matches("NI3HAO", &reading));
+        assert!(matches("ni3'hao3", &reading));
+        assert!(!matches("ni3 hao4", &reading));
+        assert!(!matches("ni", &reading));
+        assert!(!matches("nihaoma", &reading));
+    }
+
+    #[test]
+    fn test_matches_neutral_tone() {
+        let with_five = pinyin(&[("ma", "5")]);
+        let without_tone = pinyin(&[("ma", "")]);
+
+        assert!(matches("ma5", &with_five));
+        assert!(matches("ma5", &without_tone));
+        assert!(matches("ma", &without_tone));
+        assert!(!matches("ma1", &without_tone));
+    }
+
+    #[test]
+    fn test_matches_jyutping() {
+        let reading = vec![
+            JyutpingSyllable::new("jat", "1"),
+            JyutpingSyllable::new("sai", "3"),
+            JyutpingSyllable::new("jan", "4"),
+        ];
+
+        assert!(matches("jat1 sai3 jan4", &reading));
+        assert!(matches("jatsaijan", &reading));
+        assert!(matches("jat1sai jan", &reading));
+        assert!(!matches("jat1 sai3 jan5", &reading));
+        // Jyutping has no unwritten tone for 5 to stand for
+        assert!(!matches("wu5", &[JyutpingSyllable::new("wu", "")]));
+    }
+
+    #[test]
+    fn test_matches_umlaut_spellings() {
+        let reading = pinyin(&[("lu:", "4")]);
+
+        assert!(matches("lu:4", &reading));
+        assert!(matches("lv4", &reading));
+        assert!(matches("lü", &reading));
+        assert!(!matches("lu4", &reading));
+    }
+
+    #[test]
+    fn test_matches_letters_and_punctuation() {
+        let reading = vec![
+            PinyinSyllable::letter("A"),
+            PinyinSyllable::punctuation("·"),
+            PinyinSyllable::new("zhi", "4"),
+        ];
+
+        assert!(matches("A zhi4", &reading));
+        assert!(matches("azhi", &reading));
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut index = ReadingIndex::default();
+        index.push(0, &pinyin(&[("xian", "1")]));
+        index.push(1, &pinyin(&[("Xi", "1"), ("an", "1")]));
+        index.push(2, &[JyutpingSyllable::new("wu", "1")]);
+
+        assert_eq!(index.candidates("xian"), &[0, 1]);
+        assert_eq!(index.candidates("Xi1 an1"), &[0, 1]);
+        assert_eq!(index.candidates("wu1"), &[2]);
+        assert!(index.candidates("").is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+/*!
+The romanization schemes a `Syllable` can be written in. `Pinyin` and `Jyutping` are marker
+types: a `Syllable<Pinyin>` is a Mandarin syllable and a `Syllable<Jyutping>` is a Cantonese one,
+so code that receives a syllable knows how to validate it, which tones it can carry and which
+conversions apply to it.
+
+# Usage:
+```
+use cccedict::cedict_entry::CedictEntry;
+use cccedict::romanization::*;
+use cccedict::syllable::Syllable;
+
+fn invalid<R: Romanization>(syllables: &[Syllable<R>]) -> Vec<String> {
+    syllables
+        .iter()
+        .filter(|syllable| syllable.validate().is_err())
+        .map(|syllable| format!("{} {}", R::NAME, syllable))
+        .collect()
+}
+
+let entry = CedictEntry::new("你好 你好 [ni3 hao3] {nei5 hou7} /hello/").unwrap();
+assert!(invalid(entry.pinyin.as_deref().unwrap()).is_empty());
+assert_eq!(invalid(entry.jyutping.as_deref().unwrap()), vec!["jyutping hou7"]);
+```
+*/
+
+use crate::errors::{SyllableError, SyllableErrorKind};
+use crate::inventory;
+use crate::syllable::{SyllableKind, SyllableRef};
+use crate::tone::{CantoneseTone, MandarinTone};
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// A romanization scheme, implemented by the marker types `Pinyin` and `Jyutping`.
+pub trait Romanization:
+    fmt::Debug + Clone + Copy + Default + PartialEq + Eq + Hash + PartialOrd + Ord + 'static
+{
+    /// The tones a syllable in this scheme can carry
+    type Tone: fmt::Debug + fmt::Display + Clone + Copy + PartialEq + Eq + Hash + FromStr;
+
+    /// The scheme's name in lowercase, e.g. `pinyin`
+    const NAME: &'static str;
+
+    /// The tone of a syllable written without one, if the scheme has such a tone
+    const UNMARKED_TONE: Option<&'static str>;
+
+    /// The scheme's initials, longest first so that e.g. `zh` is found before `z`
+    const INITIALS: &'static [&'static str];
+
+    /// Whether `pronunciation` is a legal syllable in this scheme, ignoring case.
+    fn is_syllable(pronunciation: &str) -> bool;
+
+    /// Checks that a syllable is real and can carry its tone. Letters, punctuation and CC-CEDICT's
+    /// `xx` placeholder are always valid.
+    fn validate(syllable: SyllableRef<'_, Self>) -> Result<(), SyllableError>;
+}
+
+/// Hanyu Pinyin, as written in CC-CEDICT `[...]` readings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pinyin;
+
+/// LSHK Jyutping, as written in cantonese.org `{...}` readings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Jyutping;
+
+/// CC-CEDICT writes `xx5` for characters whose reading is unknown
+const PLACEHOLDER: &str = "xx";
+
+fn is_exempt<R>(syllable: &SyllableRef<'_, R>) -> bool {
+    syllable.kind != SyllableKind::Syllable || syllable.pronunciation == PLACEHOLDER
+}
+
+impl Romanization for Pinyin {
+    type Tone = MandarinTone;
+
+    const NAME: &'static str = "pinyin";
+
+    /// The neutral tone, which CC-CEDICT sometimes leaves unwritten
+    const UNMARKED_TONE: Option<&'static str> = Some("5");
+
+    const INITIALS: &'static [&'static str] = inventory::PINYIN_INITIALS;
+
+    fn is_syllable(pronunciation: &str) -> bool {
+        inventory::is_pinyin_syllable(pronunciation)
+    }
+
+    fn validate(syllable: SyllableRef<'_, Self>) -> Result<(), SyllableError> {
+        if is_exempt(&syllable) {
+            return Ok(());
+        }
+
+        if !Self::is_syllable(syllable.pronunciation) {
+            return Err(syllable.error(SyllableErrorKind::UnknownPinyin));
+        }
+
+        // Erhua is always unstressed
+        let is_erhua = syllable.pronunciation.eq_ignore_ascii_case("r");
+        match syllable.typed_tone() {
+            Some(tone) if is_erhua && tone != MandarinTone::Neutral => {
+                Err(syllable.error(SyllableErrorKind::ImpossiblePinyinTone))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Romanization for Jyutping {
+    type Tone = CantoneseTone;
+
+    const NAME: &'static str = "jyutping";
+
+    /// Every Cantonese syllable carries one of the six tones
+    const UNMARKED_TONE: Option<&'static str> = None;
+
+    const INITIALS: &'static [&'static str] = inventory::JYUTPING_INITIALS;
+
+    fn is_syllable(pronunciation: &str) -> bool {
+        inventory::is_jyutping_syllable(pronunciation)
+    }
+
+    fn validate(syllable: SyllableRef<'_, Self>) -> Result<(), SyllableError> {
+        if is_exempt(&syllable) {
+            return Ok(());
+        }
+
+        let pronunciation = syllable.pronunciation.to_lowercase();
+        let final_ = match inventory::split_jyutping(&pronunciation) {
+            Some((_, final_)) => final_,
+            None => return Err(syllable.error(SyllableErrorKind::UnknownJyutping)),
+        };
+
+        let possible = match syllable.typed_tone() {
+            Some(tone) if inventory::is_checked_final(final_) => matches!(
+                tone.to_six_tone(),
+                CantoneseTone::First | CantoneseTone::Third | CantoneseTone::Sixth
+            ),
+            Some(tone) => !tone.is_entering_variant(),
+            None => true,
+        };
+
+        if possible {
+            Ok(())
+        } else {
+            Err(syllable.error(SyllableErrorKind::ImpossibleJyutpingTone))
+        }
+    }
+}
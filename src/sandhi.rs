@@ -0,0 +1,194 @@
+/*!
+Mandarin tone sandhi. CC-CEDICT readings give each syllable its citation tone, but in speech
+some tones change depending on the tone that follows. `spoken` applies those changes to a copy of
+a reading, leaving the stored reading as it is.
+
+# Usage:
+```
+use cccedict::cedict_entry::CedictEntry;
+use cccedict::sandhi::*;
+use cccedict::syllable::*;
+
+let entry = CedictEntry::new("你好 你好 [ni3 hao3] /hello/").unwrap();
+assert_eq!(
+    entry.spoken_pinyin(),
+    Some(vec![Syllable::new("ni", "2"), Syllable::new("hao", "3")])
+);
+assert_eq!(entry.pinyin.unwrap()[0], Syllable::new("ni", "3"));
+
+let reading = [PinyinSyllable::new("bu", "4"), PinyinSyllable::new("shi", "4")];
+assert_eq!(spoken("不是", &reading)[0], Syllable::new("bu", "2"));
+```
+
+Three rules are applied:
+
+- A third tone before another third tone becomes a second tone. In a run of third tones, every
+  syllable but the last changes, which is how most words and short phrases are spoken; longer
+  runs may be grouped differently in speech.
+- 不 `bu4` becomes `bu2` before a fourth tone.
+- 一 `yi1` becomes `yi2` before a fourth tone and `yi4` before other tones. It keeps its first
+  tone at the end of a word and when counting, after 第 or another numeral, and is neutral
+  between a repeated verb, as in 看一看.
+
+The 一 and 不 rules need the characters of the headword to tell 一 from other syllables read
+`yi1`. When the headword and the reading don't line up one character per syllable, only the
+third tone rule is applied.
+*/
+
+use crate::syllable::{Pinyin, Syllable, SyllableKind};
+use crate::tone::MandarinTone;
+
+/// Returns `syllables` with tone sandhi applied, given the `headword` they are the reading of.
+pub fn spoken(headword: &str, syllables: &[Syllable<Pinyin>]) -> Vec<Syllable<Pinyin>> {
+    let characters: Vec<char> = headword.chars().collect();
+    let aligned = characters.len() == syllables.len();
+    let character = |idx: usize| match aligned {
+        true => characters.get(idx).copied(),
+        false => None,
+    };
+
+    let tones: Vec<Option<MandarinTone>> = syllables.iter().map(tone).collect();
+    let mut spoken_tones = tones.clone();
+
+    for (idx, syllable) in syllables.iter().enumerate() {
+        let next = tones.get(idx + 1).copied().flatten();
+        let previous_character = idx.checked_sub(1).and_then(character);
+
+        match character(idx) {
+            Some('不') if is(syllable, "bu") && next == Some(MandarinTone::Fourth) => {
+                spoken_tones[idx] = Some(MandarinTone::Second);
+            }
+            Some('一') if is(syllable, "yi") && tones[idx] == Some(MandarinTone::First) => {
+                let counting = previous_character.is_some_and(|c| NUMERALS.contains(c));
+                let between_repeats =
+                    previous_character.is_some() && previous_character == character(idx + 1);
+
+                spoken_tones[idx] = match next {
+                    _ if between_repeats => Some(MandarinTone::Neutral),
+                    _ if counting => Some(MandarinTone::First),
+                    Some(MandarinTone::Fourth) => Some(MandarinTone::Second),
+                    Some(MandarinTone::Neutral) | None => Some(MandarinTone::First),
+                    Some(_) => Some(MandarinTone::Fourth),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    // Every third tone directly followed by another third tone becomes a second tone
+    for idx in 0..spoken_tones.len().saturating_sub(1) {
+        if spoken_tones[idx] == Some(MandarinTone::Third)
+            && spoken_tones[idx + 1] == Some(MandarinTone::Third)
+        {
+            spoken_tones[idx] = Some(MandarinTone::Second);
+        }
+    }
+
+    syllables
+        .iter()
+        .zip(spoken_tones)
+        .zip(tones)
+        .map(|((syllable, spoken), citation)| match spoken {
+            Some(spoken) if Some(spoken) != citation => {
+                Syllable::new(&syllable.pronunciation, &spoken.to_string())
+            }
+            _ => syllable.clone(),
+        })
+        .collect()
+}
+
+/// Characters that make a following 一 part of a number, like 十一 or 第一
+const NUMERALS: &str = "第〇零一二三四五六七八九十百千万萬亿億两兩";
+
+fn tone(syllable: &Syllable<Pinyin>) -> Option<MandarinTone> {
+    match syllable.kind {
+        SyllableKind::Syllable => syllable.mandarin_tone(),
+        _ => None,
+    }
+}
+
+fn is(syllable: &Syllable<Pinyin>, pronunciation: &str) -> bool {
+    syllable.kind == SyllableKind::Syllable
+        && syllable.pronunciation.eq_ignore_ascii_case(pronunciation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(syllables: &str) -> Vec<Syllable<Pinyin>> {
+        syllables
+            .split(' ')
+            .map(|s| {
+                let (pronunciation, tone) = s.split_at(s.len() - 1);
+                Syllable::new(pronunciation, tone)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_third_tone() {
+        assert_eq!(spoken("你好", &reading("ni3 hao3")), reading("ni2 hao3"));
+        assert_eq!(
+            spoken("展覽館", &reading("zhan3 lan3 guan3")),
+            reading("zhan2 lan2 guan3")
+        );
+        assert_eq!(spoken("好人", &reading("hao3 ren2")), reading("hao3 ren2"));
+        assert_eq!(spoken("姐姐", &reading("jie3 jie5")), reading("jie3 jie5"));
+    }
+
+    #[test]
+    fn test_bu() {
+        assert_eq!(spoken("不是", &reading("bu4 shi4")), reading("bu2 shi4"));
+        assert_eq!(spoken("不好", &reading("bu4 hao3")), reading("bu4 hao3"));
+        assert_eq!(spoken("不", &reading("bu4")), reading("bu4"));
+        // 步 is also read bu4
+        assert_eq!(spoken("步驟", &reading("bu4 zhou4")), reading("bu4 zhou4"));
+    }
+
+    #[test]
+    fn test_yi() {
+        assert_eq!(spoken("一個", &reading("yi1 ge4")), reading("yi2 ge4"));
+        assert_eq!(spoken("一天", &reading("yi1 tian1")), reading("yi4 tian1"));
+        assert_eq!(spoken("一起", &reading("yi1 qi3")), reading("yi4 qi3"));
+        assert_eq!(spoken("統一", &reading("tong3 yi1")), reading("tong3 yi1"));
+        assert_eq!(spoken("第一", &reading("di4 yi1")), reading("di4 yi1"));
+        assert_eq!(
+            spoken("十一月", &reading("shi2 yi1 yue4")),
+            reading("shi2 yi1 yue4")
+        );
+        assert_eq!(
+            spoken("看一看", &reading("kan4 yi1 kan4")),
+            reading("kan4 yi5 kan4")
+        );
+        // 醫 is also read yi1
+        assert_eq!(spoken("醫院", &reading("yi1 yuan4")), reading("yi1 yuan4"));
+    }
+
+    #[test]
+    fn test_combined() {
+        assert_eq!(
+            spoken("一點也不想", &reading("yi1 dian3 ye3 bu4 xiang3")),
+            reading("yi4 dian2 ye3 bu4 xiang3")
+        );
+    }
+
+    #[test]
+    fn test_unaligned_headword() {
+        // Only the third tone rule applies when characters can't be matched to syllables
+        assert_eq!(
+            spoken("一", &reading("yi1 ge4 ni3 hao3")),
+            reading("yi1 ge4 ni2 hao3")
+        );
+    }
+
+    #[test]
+    fn test_letters_and_punctuation_break_runs() {
+        let syllables = vec![
+            Syllable::new("Ma", "3"),
+            Syllable::punctuation("·"),
+            Syllable::new("Li", "3"),
+        ];
+        assert_eq!(spoken("馬·李", &syllables), syllables);
+    }
+}
@@ -1,44 +1,292 @@
 /*!
-A `Syllable` represents a single syllable containing one pronunciation and one tone.
+A `Syllable` represents a single syllable containing one pronunciation and one tone. Syllables
+are typed by their romanization scheme, so pinyin syllables are `Syllable<Pinyin>`, also known as
+`PinyinSyllable`, and jyutping syllables are `Syllable<Jyutping>` or `JyutpingSyllable`.
 
 # Usage:
 ```
 use cccedict::syllable::*;
 
-let syllable = Syllable::new("ni", "3");
+let syllable = PinyinSyllable::new("ni", "3");
 
 assert_eq!(syllable.pronunciation, "ni");
 assert_eq!(syllable.tone, "3");
 ```
 
-Currently there's no validation that either pronunciation or tone are valid inputs:
+Besides syllables, CC-CEDICT readings contain Latin letters, as in `AA制 [A A zhi4]`, and
+punctuation, as in `[Yi1 li4 sha1 bai2 · Da4 wei4]`. These are kept in the same list with a
+different `SyllableKind`:
 
 ```
 # use cccedict::syllable::*;
-let syllable = Syllable::new("life", "42");
+assert_eq!(PinyinSyllable::letter("A").kind, SyllableKind::Letter);
+assert_eq!(PinyinSyllable::punctuation("·").kind, SyllableKind::Punctuation);
+assert_eq!(PinyinSyllable::new("zhi", "4").kind, SyllableKind::Syllable);
+```
+
+CC-CEDICT writes ü as `u:`, which is kept as-is in the pronunciation:
+
+```
+# use cccedict::syllable::*;
+let syllable = PinyinSyllable::new("lu:", "4");
+assert!(syllable.has_umlaut());
+```
+
+Tones are validated when an entry is parsed, and are available as `MandarinTone` or
+`CantoneseTone` depending on the syllable's scheme:
+
+```
+# use cccedict::syllable::*;
+use cccedict::tone::*;
+
+let syllable = JyutpingSyllable::new("hou", "2");
+assert_eq!(syllable.typed_tone(), Some(CantoneseTone::Second));
+assert_eq!(PinyinSyllable::new("ma", "5").mandarin_tone(), Some(MandarinTone::Neutral));
+```
+
+Syllables can be checked against the pinyin and jyutping inventories in `crate::inventory`:
+
+```
+# use cccedict::syllable::*;
+assert!(PinyinSyllable::new("hao", "3").validate().is_ok());
+assert!(PinyinSyllable::new("blorp", "3").validate().is_err());
+
+// Checked syllables only carry the entering tones 1, 3 and 6
+assert!(JyutpingSyllable::new("sik", "6").validate().is_ok());
+assert!(JyutpingSyllable::new("sik", "4").validate().is_err());
+```
+
+They can also be split into their parts, as described in `crate::phonology`:
+
+```
+# use cccedict::syllable::*;
+use cccedict::phonology::*;
+
+let parts = JyutpingSyllable::new("hou", "2").jyutping_parts().unwrap();
+assert_eq!(parts.onset, Some(JyutpingOnset::H));
+assert_eq!(parts.nucleus, JyutpingNucleus::O);
+```
+
+`Syllable::new` itself doesn't validate its input, so invalid tones have no typed tone:
+
+```
+# use cccedict::syllable::*;
+let syllable = PinyinSyllable::new("life", "42");
 
 assert_eq!(syllable.pronunciation, "life");
 assert_eq!(syllable.tone, "42");
+assert_eq!(syllable.mandarin_tone(), None);
 ```
 */
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
-pub struct Syllable {
+use crate::errors::{SyllableError, SyllableErrorKind};
+use crate::phonology::{JyutpingParts, PinyinParts};
+pub use crate::romanization::{Jyutping, Pinyin, Romanization};
+use crate::tone::{CantoneseTone, MandarinTone};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// The kind of token a reading is made of.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SyllableKind {
+    /// A romanized syllable with an optional tone number, e.g. `ni3` or `lu:4`
+    #[default]
+    Syllable,
+    /// A Latin letter read as itself, e.g. the `A`s in `AA制 [A A zhi4]`
+    Letter,
+    /// Punctuation copied from the headword, e.g. `·` or `,`
+    Punctuation,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Syllable<R> {
     pub pronunciation: String,
     /// While both jyutping and pinyin use numbers to denote tones, we are not doing mathematical
-    /// operations with them so they remain `String`s.
+    /// operations with them so they remain `String`s. See `Syllable::typed_tone` for typed
+    /// tones.
     pub tone: String,
+    pub kind: SyllableKind,
+    romanization: PhantomData<R>,
 }
 
-impl Syllable {
+pub type PinyinSyllable = Syllable<Pinyin>;
+pub type JyutpingSyllable = Syllable<Jyutping>;
+
+impl<R: Romanization> Syllable<R> {
     pub fn new(pronunciation: &str, tone: &str) -> Self {
         Syllable {
             pronunciation: pronunciation.to_string(),
             tone: tone.to_string(),
+            kind: SyllableKind::Syllable,
+            romanization: PhantomData,
+        }
+    }
+
+    pub fn letter(letter: &str) -> Self {
+        Syllable {
+            kind: SyllableKind::Letter,
+            ..Syllable::new(letter, "")
+        }
+    }
+
+    pub fn punctuation(punctuation: &str) -> Self {
+        Syllable {
+            kind: SyllableKind::Punctuation,
+            ..Syllable::new(punctuation, "")
+        }
+    }
+
+    /// The tone, or `None` if it has no valid tone for its scheme.
+    pub fn typed_tone(&self) -> Option<R::Tone> {
+        self.as_ref().typed_tone()
+    }
+
+    /// Checks that this is a real syllable that can carry its tone. Letters and punctuation are
+    /// always valid.
+    pub fn validate(&self) -> Result<(), SyllableError> {
+        self.as_ref().validate()
+    }
+
+    pub fn as_ref(&self) -> SyllableRef<'_, R> {
+        SyllableRef {
+            pronunciation: &self.pronunciation,
+            tone: &self.tone,
+            kind: self.kind,
+            romanization: PhantomData,
         }
     }
 }
 
+impl Syllable<Pinyin> {
+    /// Whether the pronunciation contains ü, written `u:` in CC-CEDICT.
+    pub fn has_umlaut(&self) -> bool {
+        self.as_ref().has_umlaut()
+    }
+
+    pub fn mandarin_tone(&self) -> Option<MandarinTone> {
+        self.typed_tone()
+    }
+
+    /// Splits the syllable into its initial, medial, final and tone.
+    pub fn pinyin_parts(&self) -> Option<PinyinParts> {
+        self.as_ref().pinyin_parts()
+    }
+}
+
+impl Syllable<Jyutping> {
+    pub fn cantonese_tone(&self) -> Option<CantoneseTone> {
+        self.typed_tone()
+    }
+
+    /// Splits the syllable into its onset, nucleus, coda and tone.
+    pub fn jyutping_parts(&self) -> Option<JyutpingParts> {
+        self.as_ref().jyutping_parts()
+    }
+}
+
+impl<R> fmt::Display for Syllable<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.pronunciation, self.tone)
+    }
+}
+
+/// A `SyllableRef` is a `Syllable` borrowed from the text it was parsed from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SyllableRef<'a, R> {
+    pub pronunciation: &'a str,
+    pub tone: &'a str,
+    pub kind: SyllableKind,
+    romanization: PhantomData<R>,
+}
+
+impl<'a, R: Romanization> SyllableRef<'a, R> {
+    pub fn new(pronunciation: &'a str, tone: &'a str) -> Self {
+        SyllableRef {
+            pronunciation,
+            tone,
+            kind: SyllableKind::Syllable,
+            romanization: PhantomData,
+        }
+    }
+
+    pub fn letter(letter: &'a str) -> Self {
+        SyllableRef {
+            kind: SyllableKind::Letter,
+            ..SyllableRef::new(letter, "")
+        }
+    }
+
+    pub fn punctuation(punctuation: &'a str) -> Self {
+        SyllableRef {
+            kind: SyllableKind::Punctuation,
+            ..SyllableRef::new(punctuation, "")
+        }
+    }
+
+    pub fn typed_tone(&self) -> Option<R::Tone> {
+        self.tone.parse().ok()
+    }
+
+    pub fn validate(&self) -> Result<(), SyllableError> {
+        R::validate(*self)
+    }
+
+    pub(crate) fn error(&self, kind: SyllableErrorKind) -> SyllableError {
+        SyllableError {
+            kind,
+            syllable: self.to_string(),
+        }
+    }
+
+    // Takes `&self` so that it isn't shadowed by `ToOwned::to_owned` when called on a reference
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_owned(&self) -> Syllable<R> {
+        Syllable {
+            pronunciation: self.pronunciation.to_string(),
+            tone: self.tone.to_string(),
+            kind: self.kind,
+            romanization: PhantomData,
+        }
+    }
+}
+
+impl<'a> SyllableRef<'a, Pinyin> {
+    pub fn has_umlaut(&self) -> bool {
+        self.kind == SyllableKind::Syllable
+            && (self.pronunciation.contains("u:") || self.pronunciation.contains("U:"))
+    }
+
+    pub fn mandarin_tone(&self) -> Option<MandarinTone> {
+        self.typed_tone()
+    }
+
+    pub fn pinyin_parts(&self) -> Option<PinyinParts> {
+        PinyinParts::new(*self)
+    }
+}
+
+impl<'a> SyllableRef<'a, Jyutping> {
+    pub fn cantonese_tone(&self) -> Option<CantoneseTone> {
+        self.typed_tone()
+    }
+
+    pub fn jyutping_parts(&self) -> Option<JyutpingParts> {
+        JyutpingParts::new(*self)
+    }
+}
+
+impl<'a, R> fmt::Display for SyllableRef<'a, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.pronunciation, self.tone)
+    }
+}
+
+impl<'a, R: Romanization> From<SyllableRef<'a, R>> for Syllable<R> {
+    fn from(syllable: SyllableRef<'a, R>) -> Self {
+        syllable.to_owned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,11 +294,90 @@ mod tests {
     #[test]
     fn test_syllable_init() {
         assert_eq!(
-            Syllable::new("ni", "3"),
+            PinyinSyllable::new("ni", "3"),
             Syllable {
                 pronunciation: "ni".to_string(),
-                tone: "3".to_string()
+                tone: "3".to_string(),
+                kind: SyllableKind::Syllable,
+                romanization: PhantomData,
             }
         )
     }
+
+    #[test]
+    fn test_syllable_display() {
+        assert_eq!(PinyinSyllable::new("ni", "3").to_string(), "ni3");
+        assert_eq!(SyllableRef::<Jyutping>::new("ma", "").to_string(), "ma");
+    }
+
+    #[test]
+    fn test_validate_pinyin() {
+        assert!(PinyinSyllable::new("Yi", "1").validate().is_ok());
+        assert!(PinyinSyllable::new("xx", "5").validate().is_ok());
+        assert!(PinyinSyllable::letter("A").validate().is_ok());
+        assert!(PinyinSyllable::new("r", "5").validate().is_ok());
+        assert_eq!(
+            PinyinSyllable::new("r", "4").validate(),
+            Err(SyllableError {
+                kind: SyllableErrorKind::ImpossiblePinyinTone,
+                syllable: "r4".into()
+            })
+        );
+        assert_eq!(
+            PinyinSyllable::new("blorp", "").validate(),
+            Err(SyllableError {
+                kind: SyllableErrorKind::UnknownPinyin,
+                syllable: "blorp".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_jyutping() {
+        assert!(JyutpingSyllable::new("jat", "1").validate().is_ok());
+        assert!(JyutpingSyllable::new("jat", "7").validate().is_ok());
+        assert!(JyutpingSyllable::new("sik", "9").validate().is_ok());
+        assert_eq!(
+            JyutpingSyllable::new("jat", "2").validate(),
+            Err(SyllableError {
+                kind: SyllableErrorKind::ImpossibleJyutpingTone,
+                syllable: "jat2".into()
+            })
+        );
+        assert_eq!(
+            JyutpingSyllable::new("hou", "8")
+                .validate()
+                .unwrap_err()
+                .kind,
+            SyllableErrorKind::ImpossibleJyutpingTone
+        );
+        assert_eq!(
+            JyutpingSyllable::new("hao", "2")
+                .validate()
+                .unwrap_err()
+                .kind,
+            SyllableErrorKind::UnknownJyutping
+        );
+    }
+
+    #[test]
+    fn test_typed_tones() {
+        assert_eq!(
+            PinyinSyllable::new("ma", "5").typed_tone(),
+            Some(MandarinTone::Neutral)
+        );
+        assert_eq!(
+            JyutpingSyllable::new("sik", "7").typed_tone(),
+            Some(CantoneseTone::Seventh)
+        );
+        // Mandarin has no seventh tone
+        assert_eq!(PinyinSyllable::new("si", "7").typed_tone(), None);
+    }
+
+    #[test]
+    fn test_syllable_ref_round_trip() {
+        let syllable = JyutpingSyllable::new("nei", "5");
+        assert_eq!(syllable.as_ref(), SyllableRef::new("nei", "5"));
+        assert_eq!(syllable.as_ref().to_owned(), syllable);
+    }
 }
@@ -0,0 +1,193 @@
+/*!
+Typed tones for the two romanizations found in CC-CEDICT and CC-Canto readings.
+
+`MandarinTone` covers the four pinyin tones plus the neutral tone, written `5` in CC-CEDICT.
+`CantoneseTone` covers the six jyutping tones, plus tones `7`–`9` which some sources use for the
+entering (checked) variants of tones 1, 3 and 6.
+
+# Usage:
+```
+use cccedict::tone::*;
+
+assert_eq!("3".parse::<MandarinTone>(), Ok(MandarinTone::Third));
+assert_eq!(MandarinTone::Neutral.number(), 5);
+assert!("6".parse::<MandarinTone>().is_err());
+
+let tone: CantoneseTone = "8".parse().unwrap();
+assert_eq!(tone, CantoneseTone::Eighth);
+assert_eq!(tone.to_six_tone(), CantoneseTone::Third);
+```
+*/
+
+use crate::errors::ToneError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MandarinTone {
+    First,
+    Second,
+    Third,
+    Fourth,
+    /// Written as tone `5` in CC-CEDICT
+    Neutral,
+}
+
+impl MandarinTone {
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(MandarinTone::First),
+            2 => Some(MandarinTone::Second),
+            3 => Some(MandarinTone::Third),
+            4 => Some(MandarinTone::Fourth),
+            5 => Some(MandarinTone::Neutral),
+            _ => None,
+        }
+    }
+
+    pub fn number(self) -> u8 {
+        match self {
+            MandarinTone::First => 1,
+            MandarinTone::Second => 2,
+            MandarinTone::Third => 3,
+            MandarinTone::Fourth => 4,
+            MandarinTone::Neutral => 5,
+        }
+    }
+}
+
+impl FromStr for MandarinTone {
+    type Err = ToneError;
+
+    fn from_str(tone: &str) -> Result<Self, Self::Err> {
+        parse_number(tone)
+            .and_then(MandarinTone::from_number)
+            .ok_or_else(|| ToneError(tone.to_string()))
+    }
+}
+
+impl fmt::Display for MandarinTone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CantoneseTone {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    /// The entering (checked) variant of the first tone
+    Seventh,
+    /// The entering (checked) variant of the third tone
+    Eighth,
+    /// The entering (checked) variant of the sixth tone
+    Ninth,
+}
+
+impl CantoneseTone {
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(CantoneseTone::First),
+            2 => Some(CantoneseTone::Second),
+            3 => Some(CantoneseTone::Third),
+            4 => Some(CantoneseTone::Fourth),
+            5 => Some(CantoneseTone::Fifth),
+            6 => Some(CantoneseTone::Sixth),
+            7 => Some(CantoneseTone::Seventh),
+            8 => Some(CantoneseTone::Eighth),
+            9 => Some(CantoneseTone::Ninth),
+            _ => None,
+        }
+    }
+
+    pub fn number(self) -> u8 {
+        match self {
+            CantoneseTone::First => 1,
+            CantoneseTone::Second => 2,
+            CantoneseTone::Third => 3,
+            CantoneseTone::Fourth => 4,
+            CantoneseTone::Fifth => 5,
+            CantoneseTone::Sixth => 6,
+            CantoneseTone::Seventh => 7,
+            CantoneseTone::Eighth => 8,
+            CantoneseTone::Ninth => 9,
+        }
+    }
+
+    /// Whether this is one of the entering tones `7`–`9`.
+    pub fn is_entering_variant(self) -> bool {
+        self.number() > 6
+    }
+
+    /// Maps the entering tones `7`, `8` and `9` onto `1`, `3` and `6`, as used by jyutping.
+    pub fn to_six_tone(self) -> Self {
+        match self {
+            CantoneseTone::Seventh => CantoneseTone::First,
+            CantoneseTone::Eighth => CantoneseTone::Third,
+            CantoneseTone::Ninth => CantoneseTone::Sixth,
+            tone => tone,
+        }
+    }
+}
+
+impl FromStr for CantoneseTone {
+    type Err = ToneError;
+
+    fn from_str(tone: &str) -> Result<Self, Self::Err> {
+        parse_number(tone)
+            .and_then(CantoneseTone::from_number)
+            .ok_or_else(|| ToneError(tone.to_string()))
+    }
+}
+
+impl fmt::Display for CantoneseTone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// Tones are always a single ASCII digit
+fn parse_number(tone: &str) -> Option<u8> {
+    match tone.as_bytes() {
+        [digit @ b'0'..=b'9'] => Some(digit - b'0'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mandarin_tone_round_trip() {
+        for number in 1..=5 {
+            let tone = MandarinTone::from_number(number).unwrap();
+            assert_eq!(tone.to_string().parse::<MandarinTone>(), Ok(tone));
+        }
+    }
+
+    #[test]
+    fn test_invalid_tones() {
+        for tone in ["", "0", "6", "33", "a"].iter() {
+            assert_eq!(
+                tone.parse::<MandarinTone>(),
+                Err(ToneError(tone.to_string()))
+            );
+        }
+
+        assert!("10".parse::<CantoneseTone>().is_err());
+        assert!("0".parse::<CantoneseTone>().is_err());
+    }
+
+    #[test]
+    fn test_cantonese_entering_tones() {
+        assert!(CantoneseTone::Ninth.is_entering_variant());
+        assert!(!CantoneseTone::Sixth.is_entering_variant());
+        assert_eq!(CantoneseTone::Seventh.to_six_tone(), CantoneseTone::First);
+        assert_eq!(CantoneseTone::Fourth.to_six_tone(), CantoneseTone::Fourth);
+    }
+}
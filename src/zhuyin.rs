@@ -0,0 +1,377 @@
+/*!
+Conversions between numbered pinyin and Zhuyin (Bopomofo), e.g. `ni3 hao3` and `ㄋㄧˇ ㄏㄠˇ`.
+
+# Usage:
+```
+use cccedict::cedict_entry::CedictEntry;
+use cccedict::syllable::Syllable;
+use cccedict::zhuyin::*;
+
+let entry = CedictEntry::new("你好嗎 你好吗 [ni3 hao3 ma5] /how are you?/").unwrap();
+let pinyin = entry.pinyin.unwrap();
+assert_eq!(pinyin.to_zhuyin(), "ㄋㄧˇ ㄏㄠˇ ˙ㄇㄚ");
+
+assert_eq!(parse_zhuyin("ㄋㄧˇ ㄏㄠˇ ˙ㄇㄚ").unwrap(), pinyin);
+assert_eq!(
+    parse_zhuyin("ㄓㄨㄥㄍㄨㄛˊ").unwrap(),
+    vec![Syllable::new("zhong", "1"), Syllable::new("guo", "2")]
+);
+```
+
+First tones are left unmarked, as is usual in Taiwan, and neutral tones are marked with `˙`
+before the syllable. Syllables without a tone are also left unmarked, so they read back as first
+tones. Erhua `r5` is written as `ㄦ` directly after the syllable it modifies, so `na3 r5` becomes
+`ㄋㄚˇㄦ`, and an unmarked `ㄦ` following another syllable reads back as `r5`. A lone `r5` has
+nothing to attach to and is written `˙ㄦ`.
+*/
+
+use crate::errors::ZhuyinInputError;
+use crate::inventory::{self, PINYIN_SYLLABLES};
+use crate::syllable::{Pinyin, Syllable, SyllableKind, SyllableRef};
+use crate::tone::MandarinTone;
+
+/// Renders numbered pinyin as Zhuyin.
+///
+/// Letters and punctuation are written as they are, and syllables that aren't pinyin are written
+/// in numbered form.
+pub trait ToZhuyin {
+    fn to_zhuyin(&self) -> String;
+}
+
+impl<'a> ToZhuyin for SyllableRef<'a, Pinyin> {
+    fn to_zhuyin(&self) -> String {
+        if self.kind != SyllableKind::Syllable {
+            return self.pronunciation.to_string();
+        }
+
+        let body = match zhuyin_body(self.pronunciation) {
+            Some(body) => body,
+            None => return self.to_string(),
+        };
+
+        match self.mandarin_tone() {
+            Some(MandarinTone::Neutral) => format!("{}{}", NEUTRAL, body),
+            Some(tone) => match tone_mark(tone) {
+                Some(mark) => format!("{}{}", body, mark),
+                None => body,
+            },
+            None => body,
+        }
+    }
+}
+
+impl ToZhuyin for Syllable<Pinyin> {
+    fn to_zhuyin(&self) -> String {
+        self.as_ref().to_zhuyin()
+    }
+}
+
+impl<'a> ToZhuyin for [SyllableRef<'a, Pinyin>] {
+    fn to_zhuyin(&self) -> String {
+        join(self.iter().copied())
+    }
+}
+
+impl ToZhuyin for [Syllable<Pinyin>] {
+    fn to_zhuyin(&self) -> String {
+        join(self.iter().map(Syllable::as_ref))
+    }
+}
+
+fn join<'a, I: Iterator<Item = SyllableRef<'a, Pinyin>>>(syllables: I) -> String {
+    let mut output = String::new();
+    let mut previous: Option<SyllableRef<Pinyin>> = None;
+
+    for syllable in syllables {
+        let attaches = is_erhua(syllable)
+            && previous.is_some_and(|previous| previous.kind == SyllableKind::Syllable);
+
+        if attaches {
+            output.push(ER);
+        } else {
+            if previous.is_some() {
+                output.push(' ');
+            }
+            output.push_str(&syllable.to_zhuyin());
+        }
+
+        previous = Some(syllable);
+    }
+
+    output
+}
+
+fn is_erhua(syllable: SyllableRef<Pinyin>) -> bool {
+    syllable.kind == SyllableKind::Syllable
+        && syllable.pronunciation.eq_ignore_ascii_case("r")
+        && matches!(syllable.mandarin_tone(), Some(MandarinTone::Neutral) | None)
+}
+
+/// Parses Zhuyin such as `ㄋㄧˇ ㄏㄠˇ` into numbered pinyin `Syllable`s.
+///
+/// Syllables may be separated by whitespace or written together. Unmarked syllables are first
+/// tones, and the neutral tone mark `˙` may come before or after the syllable. `ˉ` is accepted
+/// as an explicit first tone mark.
+pub fn parse_zhuyin(input: &str) -> Result<Vec<Syllable<Pinyin>>, ZhuyinInputError> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut syllables = vec![];
+    let mut idx = 0;
+    let mut follows_syllable = false;
+
+    while idx < chars.len() {
+        let (offset, c) = chars[idx];
+        let error = || ZhuyinInputError::new(input, offset);
+
+        if c.is_whitespace() {
+            follows_syllable = false;
+            idx += 1;
+            continue;
+        }
+
+        let neutral_first = c == NEUTRAL;
+        if neutral_first {
+            idx += 1;
+        }
+
+        let longest = (chars.len() - idx).min(MAX_BODY_LENGTH);
+        let (len, pinyin) = (1..=longest)
+            .rev()
+            .find_map(|len| {
+                let body: String = chars[idx..idx + len].iter().map(|(_, c)| c).collect();
+                pinyin_for(&body).map(|pinyin| (len, pinyin))
+            })
+            .ok_or_else(error)?;
+        idx += len;
+
+        let marked_tone = match chars.get(idx) {
+            Some((_, mark)) => parse_tone_mark(*mark),
+            None => None,
+        };
+        if marked_tone.is_some() {
+            idx += 1;
+        }
+
+        let syllable = match (neutral_first, marked_tone) {
+            (true, Some(_)) => return Err(error()),
+            (true, None) => Syllable::new(pinyin, "5"),
+            (false, Some(tone)) => Syllable::new(pinyin, &tone.to_string()),
+            (false, None) if pinyin == "er" && follows_syllable => Syllable::new("r", "5"),
+            (false, None) => Syllable::new(pinyin, "1"),
+        };
+
+        syllables.push(syllable);
+        follows_syllable = true;
+    }
+
+    Ok(syllables)
+}
+
+const NEUTRAL: char = '˙';
+const ER: char = 'ㄦ';
+
+/// `ㄓㄨㄤ` and `ㄒㄧㄥ` are as long as Zhuyin syllables get, before their tone mark
+const MAX_BODY_LENGTH: usize = 3;
+
+fn tone_mark(tone: MandarinTone) -> Option<char> {
+    match tone {
+        MandarinTone::First | MandarinTone::Neutral => None,
+        MandarinTone::Second => Some('ˊ'),
+        MandarinTone::Third => Some('ˇ'),
+        MandarinTone::Fourth => Some('ˋ'),
+    }
+}
+
+fn parse_tone_mark(mark: char) -> Option<MandarinTone> {
+    match mark {
+        'ˉ' => Some(MandarinTone::First),
+        'ˊ' => Some(MandarinTone::Second),
+        'ˇ' => Some(MandarinTone::Third),
+        'ˋ' => Some(MandarinTone::Fourth),
+        NEUTRAL => Some(MandarinTone::Neutral),
+        _ => None,
+    }
+}
+
+/// The pinyin syllable, in CC-CEDICT spelling, written as `body` in Zhuyin.
+fn pinyin_for(body: &str) -> Option<&'static str> {
+    // "er" sorts before erhua "r", which is also written ㄦ
+    PINYIN_SYLLABLES
+        .iter()
+        .copied()
+        .find(|syllable| zhuyin_body(syllable).as_deref() == Some(body))
+}
+
+/// The Zhuyin for a pinyin syllable without its tone.
+fn zhuyin_body(pronunciation: &str) -> Option<String> {
+    let (initial, final_) = inventory::split_pinyin(pronunciation)?;
+
+    let (_, zhuyin_initial) = INITIALS.iter().find(|(i, _)| *i == initial)?;
+    let (_, zhuyin_final) = FINALS.iter().find(|(f, _)| *f == final_)?;
+    Some(format!("{}{}", zhuyin_initial, zhuyin_final))
+}
+
+/// Pinyin initials and their Zhuyin.
+const INITIALS: [(&str, &str); 22] = [
+    ("", ""),
+    ("zh", "ㄓ"),
+    ("ch", "ㄔ"),
+    ("sh", "ㄕ"),
+    ("b", "ㄅ"),
+    ("p", "ㄆ"),
+    ("m", "ㄇ"),
+    ("f", "ㄈ"),
+    ("d", "ㄉ"),
+    ("t", "ㄊ"),
+    ("n", "ㄋ"),
+    ("l", "ㄌ"),
+    ("g", "ㄍ"),
+    ("k", "ㄎ"),
+    ("h", "ㄏ"),
+    ("j", "ㄐ"),
+    ("q", "ㄑ"),
+    ("x", "ㄒ"),
+    ("r", "ㄖ"),
+    ("z", "ㄗ"),
+    ("c", "ㄘ"),
+    ("s", "ㄙ"),
+];
+
+/// Pinyin finals, as spelled by `inventory::split_pinyin`, and their Zhuyin.
+const FINALS: [(&str, &str); 43] = [
+    ("", ""),
+    ("a", "ㄚ"),
+    ("o", "ㄛ"),
+    ("e", "ㄜ"),
+    ("ai", "ㄞ"),
+    ("ei", "ㄟ"),
+    ("ao", "ㄠ"),
+    ("ou", "ㄡ"),
+    ("an", "ㄢ"),
+    ("en", "ㄣ"),
+    ("ang", "ㄤ"),
+    ("eng", "ㄥ"),
+    ("ong", "ㄨㄥ"),
+    ("er", "ㄦ"),
+    ("i", "ㄧ"),
+    ("ia", "ㄧㄚ"),
+    ("io", "ㄧㄛ"),
+    ("ie", "ㄧㄝ"),
+    ("iai", "ㄧㄞ"),
+    ("iao", "ㄧㄠ"),
+    ("iou", "ㄧㄡ"),
+    ("ian", "ㄧㄢ"),
+    ("in", "ㄧㄣ"),
+    ("iang", "ㄧㄤ"),
+    ("ing", "ㄧㄥ"),
+    ("iong", "ㄩㄥ"),
+    ("u", "ㄨ"),
+    ("ua", "ㄨㄚ"),
+    ("uo", "ㄨㄛ"),
+    ("uai", "ㄨㄞ"),
+    ("uei", "ㄨㄟ"),
+    ("uan", "ㄨㄢ"),
+    ("uen", "ㄨㄣ"),
+    ("uang", "ㄨㄤ"),
+    ("ueng", "ㄨㄥ"),
+    ("ü", "ㄩ"),
+    ("üe", "ㄩㄝ"),
+    ("üan", "ㄩㄢ"),
+    ("ün", "ㄩㄣ"),
+    ("m", "ㄇ"),
+    ("n", "ㄋ"),
+    ("ng", "ㄫ"),
+    ("r", "ㄦ"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_zhuyin() {
+        assert_eq!(Syllable::new("zhi", "1").to_zhuyin(), "ㄓ");
+        assert_eq!(Syllable::new("si", "4").to_zhuyin(), "ㄙˋ");
+        assert_eq!(Syllable::new("yi", "1").to_zhuyin(), "ㄧ");
+        assert_eq!(Syllable::new("you", "3").to_zhuyin(), "ㄧㄡˇ");
+        assert_eq!(Syllable::new("liu", "2").to_zhuyin(), "ㄌㄧㄡˊ");
+        assert_eq!(Syllable::new("wei", "4").to_zhuyin(), "ㄨㄟˋ");
+        assert_eq!(Syllable::new("yong", "3").to_zhuyin(), "ㄩㄥˇ");
+        assert_eq!(Syllable::new("Zhong", "1").to_zhuyin(), "ㄓㄨㄥ");
+        assert_eq!(Syllable::new("de", "5").to_zhuyin(), "˙ㄉㄜ");
+        assert_eq!(Syllable::new("ma", "").to_zhuyin(), "ㄇㄚ");
+    }
+
+    #[test]
+    fn test_to_zhuyin_umlauts() {
+        assert_eq!(Syllable::new("lu:", "4").to_zhuyin(), "ㄌㄩˋ");
+        assert_eq!(Syllable::new("nu:e", "4").to_zhuyin(), "ㄋㄩㄝˋ");
+        assert_eq!(Syllable::new("jue", "2").to_zhuyin(), "ㄐㄩㄝˊ");
+        assert_eq!(Syllable::new("xun", "4").to_zhuyin(), "ㄒㄩㄣˋ");
+        assert_eq!(Syllable::new("yuan", "2").to_zhuyin(), "ㄩㄢˊ");
+    }
+
+    #[test]
+    fn test_to_zhuyin_erhua() {
+        let syllables = [Syllable::new("na", "3"), Syllable::new("r", "5")];
+        assert_eq!(syllables.to_zhuyin(), "ㄋㄚˇㄦ");
+        assert_eq!(Syllable::new("er", "4").to_zhuyin(), "ㄦˋ");
+        assert_eq!(Syllable::new("r", "5").to_zhuyin(), "˙ㄦ");
+    }
+
+    #[test]
+    fn test_to_zhuyin_fallbacks() {
+        let syllables = [
+            Syllable::letter("A"),
+            Syllable::new("ge", "4"),
+            Syllable::punctuation("·"),
+            Syllable::new("blorp", "1"),
+        ];
+        assert_eq!(syllables.to_zhuyin(), "A ㄍㄜˋ · blorp1");
+    }
+
+    #[test]
+    fn test_parse_zhuyin() {
+        assert_eq!(
+            parse_zhuyin("ㄋㄧˇㄏㄠˇ").unwrap(),
+            vec![Syllable::new("ni", "3"), Syllable::new("hao", "3")]
+        );
+        assert_eq!(
+            parse_zhuyin("ㄇㄚ˙ ㄌㄩˋ").unwrap(),
+            vec![Syllable::new("ma", "5"), Syllable::new("lu:", "4")]
+        );
+        assert_eq!(
+            parse_zhuyin("ㄋㄚˇㄦ ㄦˋ ㄦ").unwrap(),
+            vec![
+                Syllable::new("na", "3"),
+                Syllable::new("r", "5"),
+                Syllable::new("er", "4"),
+                Syllable::new("er", "1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_zhuyin_errors() {
+        assert_eq!(
+            parse_zhuyin("ㄋㄧˇ hao"),
+            Err(ZhuyinInputError::new("ㄋㄧˇ hao", 9))
+        );
+        assert!(parse_zhuyin("˙ㄇㄚˇ").is_err());
+        assert!(parse_zhuyin("ˇ").is_err());
+    }
+
+    #[test]
+    fn test_round_trip_inventory() {
+        for pinyin in PINYIN_SYLLABLES.iter().filter(|s| **s != "r") {
+            for tone in ["1", "2", "3", "4", "5"] {
+                let syllable = Syllable::new(pinyin, tone);
+                assert_eq!(
+                    parse_zhuyin(&syllable.to_zhuyin()).unwrap(),
+                    vec![syllable],
+                    "{}",
+                    pinyin
+                );
+            }
+        }
+    }
+}